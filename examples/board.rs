@@ -0,0 +1,73 @@
+//! Print a hexchess position in the terminal.
+//!
+//! ```sh
+//! cargo run --example board -- "<fen>" [--variant mini] [--ascii] [--color] [--flip] [--highlight f6,f7] [--moves f5]
+//! ```
+
+use hexchess::hexchess::hexchess::Hexchess;
+use hexchess::hexchess::text::{render_text, TextOptions};
+use hexchess::hexchess::variant::Variant;
+use std::env;
+use std::process::exit;
+
+fn main() {
+    let mut options = TextOptions { unicode: true, ..TextOptions::default() };
+    let mut args = env::args().skip(1);
+    let mut fen: Option<String> = None;
+    let mut variant = Variant::default();
+    let mut highlights: Vec<String> = vec![];
+    let mut moves: Vec<String> = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ascii" => options.unicode = false,
+            "--color" => options.ansi = true,
+            "--flip" => options.flipped = true,
+            "--highlight" => highlights.extend(args.next().unwrap_or_default().split(',').map(String::from)),
+            "--moves" => moves.push(args.next().unwrap_or_default()),
+            "--variant" => {
+                let name = args.next().unwrap_or_default();
+
+                match name.parse::<Variant>() {
+                    Ok(value) => variant = value,
+                    Err(err) => fail(&err),
+                }
+            },
+            _ => fen = Some(arg),
+        }
+    }
+
+    // positions are named by the variant's board, so they're resolved once the position is known
+    let hexchess = match fen {
+        Some(fen) => match Hexchess::parse_variant(&fen, variant) {
+            Ok(value) => value,
+            Err(err) => fail(&err),
+        },
+        None => Hexchess::init_variant(variant),
+    };
+
+    let geometry = hexchess.geometry();
+
+    for position in highlights {
+        match geometry.position_index(&position) {
+            Some(value) => options.highlights.push(value),
+            None => fail(&format!("invalid position: {}", position)),
+        }
+    }
+
+    for position in moves {
+        match geometry.position_index(&position) {
+            Some(from) => options.highlights.extend(hexchess.moves_from(from).iter().map(|san| san.to)),
+            None => fail(&format!("invalid position: {}", position)),
+        }
+    }
+
+    println!("{}", render_text(&hexchess, &options));
+    println!();
+    println!("{}", hexchess);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    exit(1);
+}
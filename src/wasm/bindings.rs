@@ -0,0 +1,486 @@
+use crate::constants::Color;
+use crate::hexchess::codec::{decode_game, encode_game, from_share_code, GameRecord, to_share_code};
+use crate::hexchess::game::{Game, Status};
+use crate::hexchess::geometry::Cell;
+use crate::hexchess::hexchess::{Hexchess, Pin};
+use crate::hexchess::layout::{Layout, Point};
+use crate::hexchess::san::San;
+use crate::hexchess::shuffle::{shuffle_count, shuffle_index, shuffle_position, shuffle_seeded};
+use crate::hexchess::svg::{render_svg, SvgOptions};
+use crate::hexchess::variant::Variant;
+use wasm_bindgen::prelude::*;
+
+/// Parse a color argument.
+fn parse_color(color: &str) -> Result<Color, JsError> {
+    match color {
+        "w" => Ok(Color::White),
+        "b" => Ok(Color::Black),
+        _ => Err(JsError::new(&format!("invalid color: {}", color))),
+    }
+}
+
+/// Parse a position index argument.
+fn parse_position(position: u8) -> Result<Cell, JsError> {
+    match Cell::new(position) {
+        Some(cell) => Ok(cell),
+        None => Err(JsError::new(&format!("invalid position: {}", position))),
+    }
+}
+
+fn set_panic_hook() {
+    // When the `console_error_panic_hook` feature is enabled, we can call the
+    // `set_panic_hook` function at least once during initialization, and then
+    // we will get better error messages if our code ever panics.
+    //
+    // For more details see
+    // https://github.com/rustwasm/console_error_panic_hook#readme
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+/// Game held in wasm memory, so only small results cross the js boundary.
+#[wasm_bindgen(js_name = Game)]
+pub struct GameHandle {
+    game: Game,
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl GameHandle {
+    /// Create a game from Forsyth–Edwards Notation, defaulting to the initial position of a variant.
+    #[wasm_bindgen(constructor)]
+    pub fn new(fen: Option<String>, variant: Option<Variant>) -> Result<GameHandle, JsError> {
+        set_panic_hook();
+
+        let variant = variant.unwrap_or_default();
+
+        let game = match fen {
+            Some(fen) => Game::new(Hexchess::parse_variant(fen.as_str(), variant).map_err(|err| JsError::new(&err))?),
+            None => Game::new(Hexchess::init_variant(variant)),
+        };
+
+        Ok(GameHandle { game })
+    }
+
+    /// Apply a whitespace separated sequence of moves.
+    pub fn apply(&mut self, sequence: &str) -> Result<(), JsError> {
+        self.game.apply(sequence).map_err(|err| JsError::new(&err))
+    }
+
+    /// Apply `San` object.
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, san: San) -> Result<(), JsError> {
+        self.game.apply_move(&san).map_err(|err| JsError::new(&err))
+    }
+
+    /// Get current legal moves.
+    #[wasm_bindgen(js_name = currentMoves)]
+    pub fn current_moves(&self) -> Vec<San> {
+        self.game.hexchess.current_moves()
+    }
+
+    /// Convert the current position to Forsyth–Edwards Notation.
+    pub fn fen(&self) -> String {
+        self.game.hexchess.to_string()
+    }
+
+    /// Get the moves played, in order.
+    pub fn history(&self) -> Vec<San> {
+        self.game.history()
+    }
+
+    /// Test if the current turn is in check.
+    #[wasm_bindgen(js_name = isCheck)]
+    pub fn is_check(&self) -> bool {
+        self.game.hexchess.is_check()
+    }
+
+    /// Get legal moves from a position index.
+    #[wasm_bindgen(js_name = movesFrom)]
+    pub fn moves_from(&self, position: u8) -> Result<Vec<San>, JsError> {
+        let cell = parse_position(position)?;
+
+        Ok(self.game.hexchess.moves_from(cell.index()))
+    }
+
+    /// Get the status of the current position.
+    pub fn status(&self) -> Status {
+        self.game.status()
+    }
+
+    /// Copy the current position to a `Hexchess` object.
+    #[wasm_bindgen(js_name = toHexchess)]
+    pub fn to_hexchess(&self) -> Hexchess {
+        self.game.hexchess
+    }
+
+    /// Take back the last move, returning it.
+    pub fn undo(&mut self) -> Option<San> {
+        self.game.undo()
+    }
+}
+
+/// Apply a whitespace separated sequence of move to `Hexchess` object.
+#[wasm_bindgen(js_name = apply)]
+pub fn apply(hexchess: Hexchess, sequence: String) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    let mut clone = hexchess.clone();
+
+    clone.apply(sequence.as_str()).map_err(|err| JsError::new(&err))?;
+
+    Ok(clone)
+}
+
+/// Apply `San` object to a `Hexchess` object.
+#[wasm_bindgen(js_name = applyMove)]
+pub fn apply_move(hexchess: Hexchess, san: San) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    let mut clone = hexchess.clone();
+
+    clone.apply_move(&san).map_err(|err| JsError::new(&err))?;
+
+    Ok(clone)
+}
+
+/// Apply `San` object to a `Hexchess` object, regardless of turn or legality. Checks given are not counted.
+#[wasm_bindgen(js_name = applyMoveUnsafe)]
+pub fn apply_move_unsafe(mut hexchess: Hexchess, san: San) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    hexchess.try_apply_move_unsafe(&san).map_err(|err| JsError::new(&err))?;
+
+    Ok(hexchess)
+}
+
+/// Count the number of times each position is attacked by a given color.
+#[wasm_bindgen(js_name = attackMap, skip_typescript)]
+pub fn attack_map(hexchess: Hexchess, color: &str) -> Result<Vec<u8>, JsError> {
+    set_panic_hook();
+
+    Ok(hexchess.attack_map(parse_color(color)?).to_vec())
+}
+
+/// Get positions of pieces of a given color attacking or defending a position.
+#[wasm_bindgen(js_name = attackersOf, skip_typescript)]
+pub fn attackers_of(hexchess: Hexchess, position: u8, color: &str) -> Result<Vec<u8>, JsError> {
+    set_panic_hook();
+
+    let cell = parse_position(position)?;
+
+    Ok(hexchess.attackers_of(cell.index(), parse_color(color)?))
+}
+
+/// Get the position index under a pixel coordinate.
+#[wasm_bindgen(js_name = cellAt)]
+pub fn cell_at(layout: Layout, x: f64, y: f64) -> Option<u8> {
+    set_panic_hook();
+
+    layout.cell_at(Point { x, y }).map(|cell| cell.index())
+}
+
+/// Get the pixel center of a position index.
+#[wasm_bindgen(js_name = cellCenter)]
+pub fn cell_center(layout: Layout, position: u8) -> Result<Point, JsError> {
+    set_panic_hook();
+
+    let cell = parse_position(position)?;
+
+    Ok(layout.center(cell))
+}
+
+/// Get the pixel corners of a position index, clockwise.
+#[wasm_bindgen(js_name = cellCorners)]
+pub fn cell_corners(layout: Layout, position: u8) -> Result<Vec<Point>, JsError> {
+    set_panic_hook();
+
+    let cell = parse_position(position)?;
+
+    Ok(layout.corners(cell))
+}
+
+/// Get positions of pieces giving check to the current turn.
+#[wasm_bindgen(js_name = checkers)]
+pub fn checkers(hexchess: Hexchess) -> Vec<u8> {
+    set_panic_hook();
+
+    hexchess.checkers()
+}
+
+/// Create a blank `Hexchess` object.
+#[wasm_bindgen(js_name = createHexchess)]
+pub fn create_hexchess() -> Hexchess {
+    set_panic_hook();
+
+    Hexchess::new()
+}
+
+/// Get current legal moves.
+#[wasm_bindgen(js_name = currentMoves)]
+pub fn current_moves(hexchess: Hexchess) -> Vec<San> {
+    set_panic_hook();
+
+    hexchess.current_moves()
+}
+
+/// Decode a game encoded with `encodeGame`, verifying every move is legal.
+#[wasm_bindgen(js_name = decodeGame)]
+pub fn decode_game_bytes(bytes: Vec<u8>) -> Result<GameRecord, JsError> {
+    set_panic_hook();
+
+    decode_game(&bytes).map_err(|err| JsError::new(&err))
+}
+
+/// Get positions of current turn pieces that would give check by moving off a line.
+#[wasm_bindgen(js_name = discoveredCheckCandidates)]
+pub fn discovered_check_candidates(hexchess: Hexchess) -> Vec<u8> {
+    set_panic_hook();
+
+    hexchess.discovered_check_candidates()
+}
+
+/// Encode a game as one byte per move, from an optional starting position.
+#[wasm_bindgen(js_name = encodeGame)]
+pub fn encode_game_bytes(start: Option<String>, moves: Vec<San>) -> Result<Vec<u8>, JsError> {
+    set_panic_hook();
+
+    encode_game(start.as_deref(), &moves).map_err(|err| JsError::new(&err))
+}
+
+/// Swap piece colors and rotate the board half a turn.
+#[wasm_bindgen(js_name = flipColors)]
+pub fn flip_colors(hexchess: Hexchess) -> Hexchess {
+    set_panic_hook();
+
+    hexchess.flip_colors()
+}
+
+/// Decode `Hexchess` object from a url-safe share code.
+#[wasm_bindgen(js_name = fromShareCode)]
+pub fn from_share_code_string(code: String) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    from_share_code(code.as_str()).map_err(|err| JsError::new(&err))
+}
+
+/// Create `Hexchess` object at the initial position, of Gliński's variant unless another is given.
+#[wasm_bindgen(js_name = initHexchess)]
+pub fn init_hexchess(variant: Option<Variant>) -> Hexchess {
+    set_panic_hook();
+
+    Hexchess::init_variant(variant.unwrap_or_default())
+}
+
+/// Find the king of a given color.
+#[wasm_bindgen(js_name = findKing, skip_typescript)]
+pub fn find_king(hexchess: Hexchess, color: &str) -> Result<JsValue, JsError> {
+    set_panic_hook();
+
+    Ok(JsValue::from(hexchess.find_king(parse_color(color)?)))
+}
+
+/// Get piece at position
+#[wasm_bindgen(js_name = get)]
+pub fn get(hexchess: Hexchess, position: String) -> JsValue {
+    set_panic_hook();
+
+    match hexchess.get(position.as_str()) {
+        Some(piece) => JsValue::from_str(&piece.to_string()),
+        None => JsValue::NULL,
+    }
+}
+
+/// Test if the board is in check.
+#[wasm_bindgen(js_name = isCheck)]
+pub fn is_check(hexchess: Hexchess) -> bool {
+    set_panic_hook();
+
+    hexchess.is_check()
+}
+
+
+/// Test if the board is in checkmate.
+#[wasm_bindgen(js_name = isCheckmate)]
+pub fn is_checkmate(hexchess: Hexchess) -> bool {
+    set_panic_hook();
+
+    hexchess.is_checkmate()
+}
+
+/// Test if the board is in stalemate.
+#[wasm_bindgen(js_name = isStalemate)]
+pub fn is_stalemate(hexchess: Hexchess) -> bool {
+    set_panic_hook();
+
+    hexchess.is_stalemate()
+}
+
+/// Get the pixel height of the board.
+#[wasm_bindgen(js_name = layoutHeight)]
+pub fn layout_height(layout: Layout) -> f64 {
+    set_panic_hook();
+
+    layout.height()
+}
+
+/// Get the pixel width of the board.
+#[wasm_bindgen(js_name = layoutWidth)]
+pub fn layout_width(layout: Layout) -> f64 {
+    set_panic_hook();
+
+    layout.width()
+}
+
+/// Reflect the board across the f-file.
+#[wasm_bindgen(js_name = mirror)]
+pub fn mirror(hexchess: Hexchess) -> Hexchess {
+    set_panic_hook();
+
+    hexchess.mirror()
+}
+
+/// Get legal moves from a position index.
+#[wasm_bindgen(js_name = movesFrom)]
+pub fn moves_from(hexchess: Hexchess, position: u8) -> Result<Vec<San>, JsError> {
+    set_panic_hook();
+
+    let cell = parse_position(position)?;
+
+    Ok(hexchess.moves_from(cell.index()))
+}
+
+/// Get all possible moves, including ones that result in self-check.
+#[wasm_bindgen(js_name = movesFromUnsafe)]
+pub fn moves_from_unsafe(hexchess: Hexchess, position: u8) -> Result<Vec<San>, JsError> {
+    set_panic_hook();
+
+    let cell = parse_position(position)?;
+
+    Ok(hexchess.moves_from_unsafe(cell.index()))
+}
+
+/// Parse `Hexchess` object from Forsyth–Edwards Notation, of Gliński's variant unless another is given.
+#[wasm_bindgen(js_name = parseHexchess)]
+pub fn parse_hexchess(source: String, variant: Option<Variant>) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    Hexchess::parse_variant(source.as_str(), variant.unwrap_or_default()).map_err(|err| JsError::new(&err))
+}
+
+/// Parse `Hexchess` object from Forsyth–Edwards Notation, of Gliński's variant unless another is given, rejecting unreachable positions.
+#[wasm_bindgen(js_name = parseHexchessStrict)]
+pub fn parse_hexchess_strict(source: String, variant: Option<Variant>) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    Hexchess::parse_strict_variant(source.as_str(), variant.unwrap_or_default()).map_err(|err| JsError::new(&err))
+}
+
+/// Parse `San` object from string, using the position names of Gliński's variant unless another is given.
+#[wasm_bindgen(js_name = parseSan)]
+pub fn parse_san(source: String, variant: Option<Variant>) -> Result<San, JsError> {
+    set_panic_hook();
+
+    variant.unwrap_or_default().parse_san(source.as_str()).map_err(|err| JsError::new(&err))
+}
+
+/// Get pieces pinned to the king of a given color.
+#[wasm_bindgen(js_name = pinned, skip_typescript)]
+pub fn pinned(hexchess: Hexchess, color: &str) -> Result<Vec<Pin>, JsError> {
+    set_panic_hook();
+
+    Ok(hexchess.pinned(parse_color(color)?))
+}
+
+/// Copy `Hexchess` object with only what a given color can see.
+#[wasm_bindgen(js_name = redactedView, skip_typescript)]
+pub fn redacted_view(hexchess: Hexchess, color: &str) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    Ok(hexchess.redacted_view(parse_color(color)?))
+}
+
+/// Render `Hexchess` object as a standalone SVG diagram.
+#[wasm_bindgen(js_name = renderSvg)]
+pub fn render_svg_diagram(hexchess: Hexchess, options: Option<SvgOptions>) -> String {
+    set_panic_hook();
+
+    render_svg(&hexchess, &options.unwrap_or_default())
+}
+
+/// Count the shuffled starting positions.
+#[wasm_bindgen(js_name = shuffleCount)]
+pub fn shuffle_count_number() -> u32 {
+    set_panic_hook();
+
+    shuffle_count()
+}
+
+/// Create `Hexchess` object at a shuffled starting position, by it's index.
+#[wasm_bindgen(js_name = shuffleHexchess)]
+pub fn shuffle_hexchess(index: u32) -> Result<Hexchess, JsError> {
+    set_panic_hook();
+
+    shuffle_position(index).map_err(|err| JsError::new(&err))
+}
+
+/// Find the index of a shuffled starting position.
+#[wasm_bindgen(js_name = shuffleIndex)]
+pub fn shuffle_index_of(hexchess: Hexchess) -> Option<u32> {
+    set_panic_hook();
+
+    shuffle_index(&hexchess)
+}
+
+/// Create `Hexchess` object at a shuffled starting position chosen by a seed.
+#[wasm_bindgen(js_name = shuffleSeeded)]
+pub fn shuffle_seeded_hexchess(seed: u32) -> Hexchess {
+    set_panic_hook();
+
+    shuffle_seeded(seed as u64)
+}
+
+/// Convert `Hexchess` object to string using Forsyth-Edwards Notation.
+#[wasm_bindgen(js_name = stringifyHexchess)]
+pub fn stringify_hexchess(hexchess: Hexchess) -> String {
+    set_panic_hook();
+
+    hexchess.to_string()
+}
+
+/// Convert `San` object to string, using the position names of Gliński's variant unless another is given.
+#[wasm_bindgen(js_name = stringifySan)]
+pub fn stringify_san(san: San, variant: Option<Variant>) -> Result<String, JsError> {
+    set_panic_hook();
+
+    let geometry = variant.unwrap_or_default().geometry();
+
+    if !geometry.contains(san.to) || (san.dropped().is_none() && !geometry.contains(san.from)) {
+        return Err(JsError::new(&format!("invalid move: {:?}", san)));
+    }
+
+    Ok(geometry.format_san(&san))
+}
+
+/// Encode `Hexchess` object as a url-safe share code.
+#[wasm_bindgen(js_name = toShareCode)]
+pub fn to_share_code_string(hexchess: Hexchess) -> Result<String, JsError> {
+    set_panic_hook();
+
+    to_share_code(&hexchess).map_err(|err| JsError::new(&err))
+}
+
+/// Find every reason a `Hexchess` object could not have been reached, empty when valid.
+#[wasm_bindgen(js_name = validateHexchess)]
+pub fn validate_hexchess(hexchess: Hexchess) -> Vec<String> {
+    set_panic_hook();
+
+    hexchess.validate().err().unwrap_or_default()
+}
+
+/// Get positions visible to a given color, as 1 for visible and 0 for hidden.
+#[wasm_bindgen(js_name = visibleCells, skip_typescript)]
+pub fn visible_cells(hexchess: Hexchess, color: &str) -> Result<Vec<u8>, JsError> {
+    set_panic_hook();
+
+    Ok(hexchess.visible_cells(parse_color(color)?).map(|visible| visible as u8).to_vec())
+}
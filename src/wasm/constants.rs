@@ -1,6 +1,9 @@
 use crate::h;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 /// Initial game position
@@ -1384,56 +1387,99 @@ pub const HEXBOARD_GRAPH: [[Option<u8>; 12]; 91] = [
 ];
 
 /// Piece color
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
-#[wasm_bindgen(skip_typescript)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen(skip_typescript))]
 pub enum Color {
-    #[serde(rename(deserialize = "b", serialize = "b"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "b", serialize = "b")))]
     Black,
-    #[serde(rename(deserialize = "w", serialize = "w"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "w", serialize = "w")))]
     White,
 }
 
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Color::Black => write!(f, "b"),
+            Color::White => write!(f, "w"),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "b" => Ok(Color::Black),
+            "w" => Ok(Color::White),
+            _ => Err(format!("invalid color: {}", source)),
+        }
+    }
+}
+
 /// Piece symbols
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
-#[wasm_bindgen(skip_typescript)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen(skip_typescript))]
 pub enum Piece {
-    #[serde(rename(deserialize = "P", serialize = "P"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "P", serialize = "P")))]
     WhitePawn,
 
-    #[serde(rename(deserialize = "N", serialize = "N"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "N", serialize = "N")))]
     WhiteKnight,
 
-    #[serde(rename(deserialize = "B", serialize = "B"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "B", serialize = "B")))]
     WhiteBishop,
 
-    #[serde(rename(deserialize = "R", serialize = "R"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "R", serialize = "R")))]
     WhiteRook,
 
-    #[serde(rename(deserialize = "Q", serialize = "Q"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "Q", serialize = "Q")))]
     WhiteQueen,
 
-    #[serde(rename(deserialize = "K", serialize = "K"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "K", serialize = "K")))]
     WhiteKing,
 
-    #[serde(rename(deserialize = "p", serialize = "p"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "p", serialize = "p")))]
     BlackPawn,
 
-    #[serde(rename(deserialize = "n", serialize = "n"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "n", serialize = "n")))]
     BlackKnight,
 
-    #[serde(rename(deserialize = "b", serialize = "b"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "b", serialize = "b")))]
     BlackBishop,
 
-    #[serde(rename(deserialize = "r", serialize = "r"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "r", serialize = "r")))]
     BlackRook,
 
-    #[serde(rename(deserialize = "q", serialize = "q"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "q", serialize = "q")))]
     BlackQueen,
 
-    #[serde(rename(deserialize = "k", serialize = "k"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "k", serialize = "k")))]
     BlackKing,
 }
 
+impl Piece {
+    /// get the unicode chess glyph of a piece
+    pub fn glyph(&self) -> char {
+        match *self {
+            Piece::BlackBishop => '♝',
+            Piece::BlackKing => '♚',
+            Piece::BlackKnight => '♞',
+            Piece::BlackPawn => '♟',
+            Piece::BlackQueen => '♛',
+            Piece::BlackRook => '♜',
+            Piece::WhiteBishop => '♗',
+            Piece::WhiteKing => '♔',
+            Piece::WhiteKnight => '♘',
+            Piece::WhitePawn => '♙',
+            Piece::WhiteQueen => '♕',
+            Piece::WhiteRook => '♖',
+        }
+    }
+}
+
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match *self {
@@ -1454,23 +1500,103 @@ impl fmt::Display for Piece {
     }
 }
 
+impl FromStr for Piece {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut chars = source.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Piece::try_from(c),
+            _ => Err(format!("invalid piece: {}", source)),
+        }
+    }
+}
+
+impl TryFrom<char> for Piece {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'b' => Ok(Piece::BlackBishop),
+            'k' => Ok(Piece::BlackKing),
+            'n' => Ok(Piece::BlackKnight),
+            'p' => Ok(Piece::BlackPawn),
+            'q' => Ok(Piece::BlackQueen),
+            'r' => Ok(Piece::BlackRook),
+            'B' => Ok(Piece::WhiteBishop),
+            'K' => Ok(Piece::WhiteKing),
+            'N' => Ok(Piece::WhiteKnight),
+            'P' => Ok(Piece::WhitePawn),
+            'Q' => Ok(Piece::WhiteQueen),
+            'R' => Ok(Piece::WhiteRook),
+            _ => Err(format!("invalid piece: {}", c)),
+        }
+    }
+}
+
 /// Promotion pieces
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
-#[wasm_bindgen(skip_typescript)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen(skip_typescript))]
 pub enum PromotionPiece {
-    #[serde(rename(deserialize = "b", serialize = "b"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "b", serialize = "b")))]
     Bishop,
 
-    #[serde(rename(deserialize = "n", serialize = "n"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "k", serialize = "k")))]
+    King,
+
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "n", serialize = "n")))]
     Knight,
 
-    #[serde(rename(deserialize = "q", serialize = "q"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "q", serialize = "q")))]
     Queen,
 
-    #[serde(rename(deserialize = "r", serialize = "r"))]
+    #[cfg_attr(feature = "serde", serde(rename(deserialize = "r", serialize = "r")))]
     Rook,
 }
 
+impl fmt::Display for PromotionPiece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            PromotionPiece::Bishop => 'b',
+            PromotionPiece::King => 'k',
+            PromotionPiece::Knight => 'n',
+            PromotionPiece::Queen => 'q',
+            PromotionPiece::Rook => 'r',
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+impl FromStr for PromotionPiece {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut chars = source.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => PromotionPiece::try_from(c),
+            _ => Err(format!("invalid promotion piece: {}", source)),
+        }
+    }
+}
+
+impl TryFrom<char> for PromotionPiece {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'b' => Ok(PromotionPiece::Bishop),
+            'k' => Ok(PromotionPiece::King),
+            'n' => Ok(PromotionPiece::Knight),
+            'q' => Ok(PromotionPiece::Queen),
+            'r' => Ok(PromotionPiece::Rook),
+            _ => Err(format!("invalid promotion piece: {}", c)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1490,4 +1616,51 @@ mod tests {
         assert_eq!(format!("{}", Piece::WhiteQueen), "Q");
         assert_eq!(format!("{}", Piece::WhiteRook), "R");
     }
+
+    #[test]
+    fn test_color_round_trip() {
+        for color in [Color::Black, Color::White] {
+            assert_eq!(color.to_string().parse::<Color>(), Ok(color));
+        }
+
+        assert_eq!("x".parse::<Color>(), Err("invalid color: x".to_string()));
+    }
+
+    #[test]
+    fn test_piece_round_trip() {
+        for c in "bknpqrBKNPQR".chars() {
+            let piece = Piece::try_from(c).unwrap();
+
+            assert_eq!(piece.to_string(), c.to_string());
+            assert_eq!(piece.to_string().parse::<Piece>(), Ok(piece));
+        }
+
+        assert_eq!("x".parse::<Piece>(), Err("invalid piece: x".to_string()));
+        assert_eq!("pp".parse::<Piece>(), Err("invalid piece: pp".to_string()));
+        assert_eq!("".parse::<Piece>(), Err("invalid piece: ".to_string()));
+    }
+
+    #[test]
+    fn test_promotion_piece_round_trip() {
+        for c in "bknqr".chars() {
+            let piece = PromotionPiece::try_from(c).unwrap();
+
+            assert_eq!(piece.to_string().parse::<PromotionPiece>(), Ok(piece));
+        }
+
+        assert_eq!("p".parse::<PromotionPiece>(), Err("invalid promotion piece: p".to_string()));
+    }
+
+    #[test]
+    fn test_hash_keys() {
+        use std::collections::HashMap;
+
+        let mut counts: HashMap<Piece, u8> = HashMap::new();
+
+        *counts.entry(Piece::WhitePawn).or_default() += 1;
+        *counts.entry(Piece::WhitePawn).or_default() += 1;
+
+        assert_eq!(counts.get(&Piece::WhitePawn), Some(&2));
+        assert!(Color::Black < Color::White);
+    }
 }
@@ -0,0 +1,181 @@
+use crate::constants::Piece;
+use crate::hexchess::variant::{CellSet, Variant};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, IndexMut};
+
+/// Pieces on the cells of a board
+///
+/// Pieces are packed in fen order of the board's own cells into a fixed array
+/// sized for the largest board, so every board has the same size in memory.
+/// Only the board's own cells are serialized. Positions are indexed the same
+/// way on every board, and cells outside of the board are always empty.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(into = "Vec<Option<Piece>>", try_from = "Vec<Option<Piece>>"))]
+pub struct Board {
+    /// Cells on the board
+    cells: CellSet,
+
+    /// Pieces of each cell, only the first `cells.len()` are used
+    pieces: [Option<Piece>; 91],
+}
+
+impl Board {
+    /// create an empty board of the given cells
+    pub fn new(cells: CellSet) -> Self {
+        Self {
+            cells,
+            pieces: [None; 91],
+        }
+    }
+
+    /// get the cells on the board
+    pub fn cells(&self) -> CellSet {
+        self.cells
+    }
+
+    /// get the piece at a position, or none if the position is off the board
+    pub fn get(&self, position: usize) -> Option<&Option<Piece>> {
+        self.slot(position).map(|slot| &self.pieces[slot])
+    }
+
+    /// iterate over positions on the board and their pieces, in fen order
+    pub fn iter(&self) -> impl Iterator<Item = (u8, Option<Piece>)> + '_ {
+        self.cells.iter().zip(self.pieces.iter().copied())
+    }
+
+    /// count the cells on the board
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// test if the board has no cells
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// get the pieces of each cell, in fen order
+    pub fn pieces(&self) -> &[Option<Piece>] {
+        &self.pieces[..self.len()]
+    }
+
+    /// get where a position's piece is stored
+    fn slot(&self, position: usize) -> Option<usize> {
+        u8::try_from(position)
+            .ok()
+            .filter(|position| self.cells.contains(*position))
+            .map(|position| self.cells.count_before(position))
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(Variant::Glinski.geometry().cells)
+    }
+}
+
+impl Index<usize> for Board {
+    type Output = Option<Piece>;
+
+    fn index(&self, position: usize) -> &Self::Output {
+        self.get(position).unwrap_or(&None)
+    }
+}
+
+impl IndexMut<usize> for Board {
+    fn index_mut(&mut self, position: usize) -> &mut Self::Output {
+        match self.slot(position) {
+            Some(slot) => &mut self.pieces[slot],
+            None => panic!("position is not on the board: {}", position),
+        }
+    }
+}
+
+impl From<Board> for Vec<Option<Piece>> {
+    fn from(board: Board) -> Self {
+        board.pieces().to_vec()
+    }
+}
+
+/// Boards are matched to a variant's cells by their number of pieces
+impl TryFrom<Vec<Option<Piece>>> for Board {
+    type Error = String;
+
+    fn try_from(pieces: Vec<Option<Piece>>) -> Result<Self, Self::Error> {
+        let cells = match Variant::ALL.iter().map(|variant| variant.geometry().cells).find(|cells| cells.len() == pieces.len()) {
+            Some(cells) => cells,
+            None => return Err(format!("invalid board length: {}", pieces.len())),
+        };
+
+        let mut board = Board::new(cells);
+
+        board.pieces[..pieces.len()].copy_from_slice(&pieces);
+
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexchess::utils::index;
+
+    fn at(name: &str) -> usize {
+        index(name).unwrap() as usize
+    }
+
+    #[test]
+    fn packs_the_cells_of_the_board() {
+        let mut board = Board::new(Variant::Mini.geometry().cells);
+
+        board[at("f9")] = Some(Piece::BlackRook);
+        board[at("f3")] = Some(Piece::WhiteRook);
+
+        assert_eq!(board.len(), 37);
+        assert_eq!(board.pieces().len(), 37);
+        assert_eq!(board.pieces()[0], Some(Piece::BlackRook));
+        assert_eq!(board.pieces()[33], Some(Piece::WhiteRook));
+        assert_eq!(board[at("f3")], Some(Piece::WhiteRook));
+    }
+
+    #[test]
+    fn positions_off_the_board_are_empty() {
+        let board = Board::new(Variant::Mini.geometry().cells);
+
+        assert_eq!(board[at("a1")], None);
+        assert_eq!(board.get(at("a1")), None);
+        assert_eq!(board.get(at("f6")), Some(&None));
+        assert_eq!(board.get(200), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "position is not on the board: 0")]
+    fn cannot_place_pieces_off_the_board() {
+        let mut board = Board::new(Variant::Mini.geometry().cells);
+
+        board[at("f11")] = Some(Piece::BlackKing);
+    }
+
+    #[test]
+    fn iterates_in_fen_order() {
+        let board = Board::new(Variant::Mini.geometry().cells);
+        let positions: Vec<u8> = board.iter().map(|(position, _)| position).collect();
+
+        assert_eq!(positions, Variant::Mini.geometry().cells.iter().collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn converts_from_pieces() {
+        let mut pieces = vec![None; 37];
+        pieces[0] = Some(Piece::WhiteRook);
+
+        let board = Board::try_from(pieces.clone()).unwrap();
+
+        assert_eq!(board.cells(), Variant::Mini.geometry().cells);
+        assert_eq!(board[at("f9")], Some(Piece::WhiteRook));
+        assert_eq!(Vec::from(board), pieces);
+        assert_eq!(Board::try_from(vec![None; 91]), Ok(Board::default()));
+        assert_eq!(Board::try_from(vec![None; 3]), Err("invalid board length: 3".to_string()));
+    }
+}
@@ -0,0 +1,778 @@
+use crate::constants::{Color, Piece, PromotionPiece};
+use crate::hexchess::hexchess::Hexchess;
+use crate::hexchess::san::{drop_index, San, DROP_PIECES};
+use crate::hexchess::variant::Variant;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify_next::Tsify;
+
+use crate::hexchess::utils::{get_color, step, swap_color};
+
+/// Number of bytes in an encoded position
+pub const POSITION_BYTES: usize = 35;
+
+/// Most pieces an encoded position can hold
+pub const MAX_PIECES: usize = 36;
+
+const OCCUPANCY_BYTES: usize = 12;
+const PIECE_BYTES: usize = MAX_PIECES / 2;
+const NO_EN_PASSANT: u8 = 0xFF;
+
+/// Piece nibble marking the end of pieces in hand
+const END_OF_POCKETS: u8 = 0x0F;
+
+/// Piece nibble marking that the next piece on the board was promoted
+const PROMOTED: u8 = 0x0E;
+
+/// First spare occupancy bit, holding castling rights in KQkq order, or two
+/// bits of checks given by each color in variants with a check limit
+const CASTLING_BIT: usize = 91;
+
+/// Version of the encoded game format, bumped whenever move ordering changes
+pub const GAME_VERSION: u8 = 1;
+
+/// Flag set when an encoded game has a custom starting position
+const CUSTOM_START: u8 = 0x01;
+
+/// Move index marking that a two byte index follows
+const WIDE_INDEX: u8 = 0xFF;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Pieces in nibble order
+const PIECES: [Piece; 12] = [
+    Piece::WhitePawn,
+    Piece::WhiteKnight,
+    Piece::WhiteBishop,
+    Piece::WhiteRook,
+    Piece::WhiteQueen,
+    Piece::WhiteKing,
+    Piece::BlackPawn,
+    Piece::BlackKnight,
+    Piece::BlackBishop,
+    Piece::BlackRook,
+    Piece::BlackQueen,
+    Piece::BlackKing,
+];
+
+/// Promotion pieces in two bit order
+const PROMOTIONS: [PromotionPiece; 4] = [
+    PromotionPiece::Bishop,
+    PromotionPiece::Knight,
+    PromotionPiece::Queen,
+    PromotionPiece::Rook,
+];
+
+/// Pack a move into 16 bits, 7 for the from position, 7 for the target, and 2
+/// for the promotion piece. Promotions are always a single step, so their target
+/// is stored as 91 plus the direction of that step, or 103 plus the direction for
+/// promotions to a king. Drops keep their `from` of 91 plus the dropped piece.
+pub fn encode_san(san: &San) -> Result<u16, String> {
+    if (san.from > 90 && (san.dropped().is_none() || san.promotion.is_some())) || san.to > 90 {
+        return Err(format!("invalid move: {:?}", san));
+    }
+
+    let (to, promotion) = match san.promotion {
+        Some(promotion) => match (0u8..12u8).find(|n| step(san.from, *n) == Some(san.to)) {
+            Some(direction) => match PROMOTIONS.iter().position(|p| *p == promotion) {
+                Some(index) => (91 + direction, index as u16),
+                None => (103 + direction, 0),
+            },
+            None => return Err(format!("invalid promotion: {}", san)),
+        },
+        None => (san.to, 0),
+    };
+
+    Ok((san.from as u16) << 9 | (to as u16) << 2 | promotion)
+}
+
+/// Unpack a move from 16 bits
+pub fn decode_san(value: u16) -> Result<San, String> {
+    let from = (value >> 9) as u8;
+    let to = (value >> 2 & 0x7F) as u8;
+    let promotion = (value & 0x03) as usize;
+
+    if from > 90 {
+        return match (San { from, promotion: None, to }.dropped(), to, promotion) {
+            (Some(_), 0..=90, 0) => Ok(San { from, promotion: None, to }),
+            _ => Err(format!("invalid encoded move: {}", value)),
+        };
+    }
+
+    match to {
+        0..=90 => match promotion {
+            0 => Ok(San { from, promotion: None, to }),
+            _ => Err(format!("invalid encoded move: {}", value)),
+        },
+        91..=102 => match step(from, to - 91) {
+            Some(to) => Ok(San { from, promotion: Some(PROMOTIONS[promotion]), to }),
+            None => Err(format!("invalid encoded move: {}", value)),
+        },
+        103..=114 => match (step(from, to - 103), promotion) {
+            (Some(to), 0) => Ok(San { from, promotion: Some(PromotionPiece::King), to }),
+            _ => Err(format!("invalid encoded move: {}", value)),
+        },
+        _ => Err(format!("invalid encoded move: {}", value)),
+    }
+}
+
+/// Pack a position into a fixed number of bytes. A 91 bit occupancy map, with
+/// castling rights in the spare bits that follow it, is followed by a nibble
+/// for each occupied position, then the turn with the variant in it's upper
+/// nibble, en passant position, halfmove, and big-endian fullmove. Pieces in
+/// hand follow the board's nibbles as pieces of the color holding them, ending
+/// with `END_OF_POCKETS` when there is room left, and promoted pieces on the
+/// board are preceded by a `PROMOTED` nibble.
+pub fn encode_position(hexchess: &Hexchess) -> Result<[u8; POSITION_BYTES], String> {
+    let mut bytes = [0u8; POSITION_BYTES];
+    let mut count = 0;
+
+    for (index, piece) in hexchess.board.iter() {
+        let (index, piece) = match piece {
+            Some(piece) => (index as usize, piece),
+            None => continue,
+        };
+
+        if hexchess.promoted.contains(index as u8) {
+            if count == MAX_PIECES {
+                return Err(format!("too many pieces to encode: more than {}", MAX_PIECES));
+            }
+
+            write_nibble(&mut bytes, count, PROMOTED);
+            count += 1;
+        }
+
+        if count == MAX_PIECES {
+            return Err(format!("too many pieces to encode: more than {}", MAX_PIECES));
+        }
+
+        let nibble = PIECES.iter().position(|p| *p == piece).unwrap() as u8;
+
+        bytes[index / 8] |= 0x80 >> (index % 8);
+        write_nibble(&mut bytes, count, nibble);
+
+        count += 1;
+    }
+
+    if hexchess.variant.has_drops() {
+        let mut nibbles: Vec<u8> = vec![];
+
+        for (color, pocket) in [Color::White, Color::Black].iter().zip(hexchess.pockets) {
+            for (piece, held) in DROP_PIECES.iter().zip(pocket) {
+                let piece = match color {
+                    Color::Black => swap_color(piece),
+                    Color::White => *piece,
+                };
+
+                let nibble = PIECES.iter().position(|p| *p == piece).unwrap() as u8;
+
+                nibbles.extend(std::iter::repeat_n(nibble, held as usize));
+            }
+        }
+
+        if count + nibbles.len() > MAX_PIECES {
+            return Err(format!("too many pieces to encode: more than {}", MAX_PIECES));
+        }
+
+        if count + nibbles.len() < MAX_PIECES {
+            nibbles.push(END_OF_POCKETS);
+        }
+
+        for nibble in nibbles {
+            write_nibble(&mut bytes, count, nibble);
+            count += 1;
+        }
+    }
+
+    for (i, right) in hexchess.castling.iter().enumerate() {
+        if *right {
+            bytes[(CASTLING_BIT + i) / 8] |= 0x80 >> ((CASTLING_BIT + i) % 8);
+        }
+    }
+
+    if hexchess.variant.check_limit().is_some() {
+        for (i, checks) in hexchess.checks.iter().enumerate() {
+            if *checks > 3 {
+                return Err(format!("too many checks to encode: {}", checks));
+            }
+
+            for bit in 0..2 {
+                if checks & (0b10 >> bit) != 0 {
+                    bytes[(CASTLING_BIT + i * 2 + bit) / 8] |= 0x80 >> ((CASTLING_BIT + i * 2 + bit) % 8);
+                }
+            }
+        }
+    }
+
+    let offset = OCCUPANCY_BYTES + PIECE_BYTES;
+
+    let variant = Variant::ALL.iter().position(|v| *v == hexchess.variant).unwrap() as u8;
+
+    bytes[offset] = variant << 4 | match hexchess.turn {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+
+    bytes[offset + 1] = hexchess.ep.unwrap_or(NO_EN_PASSANT);
+    bytes[offset + 2] = hexchess.halfmove;
+    bytes[offset + 3..offset + 5].copy_from_slice(&hexchess.fullmove.to_be_bytes());
+
+    Ok(bytes)
+}
+
+/// Unpack a position from bytes
+pub fn decode_position(bytes: &[u8]) -> Result<Hexchess, String> {
+    if bytes.len() != POSITION_BYTES {
+        return Err(format!("invalid encoded position length: {}", bytes.len()));
+    }
+
+    let offset = OCCUPANCY_BYTES + PIECE_BYTES;
+
+    let mut count = 0;
+    let mut kings = [false; 2];
+
+    let mut hexchess = match Variant::ALL.get((bytes[offset] >> 4) as usize) {
+        Some(variant) => Hexchess::new_variant(*variant),
+        None => return Err(format!("invalid variant: {}", bytes[offset] >> 4)),
+    };
+
+    for index in 0..OCCUPANCY_BYTES * 8 {
+        if bytes[index / 8] & (0x80 >> (index % 8)) == 0 {
+            continue;
+        }
+
+        if (CASTLING_BIT..CASTLING_BIT + 4).contains(&index) {
+            let bit = index - CASTLING_BIT;
+
+            if hexchess.variant.check_limit().is_some() {
+                hexchess.checks[bit / 2] |= 0b10 >> (bit % 2);
+                continue;
+            }
+
+            if hexchess.geometry().castling[index - CASTLING_BIT].is_none() {
+                return Err("invalid occupancy".to_string());
+            }
+
+            hexchess.castling[index - CASTLING_BIT] = true;
+            continue;
+        }
+
+        if index > 90 || !hexchess.geometry().contains(index as u8) {
+            return Err("invalid occupancy".to_string());
+        }
+
+        if count == MAX_PIECES {
+            return Err("too many pieces".to_string());
+        }
+
+        let mut nibble = read_nibble(bytes, count);
+
+        if nibble == PROMOTED && hexchess.variant.has_drops() {
+            count += 1;
+
+            if count == MAX_PIECES {
+                return Err("too many pieces".to_string());
+            }
+
+            nibble = read_nibble(bytes, count);
+            hexchess.promoted.insert(index as u8);
+        }
+
+        let piece = match PIECES.get(nibble as usize) {
+            Some(piece) => *piece,
+            None => return Err(format!("invalid piece: {}", nibble)),
+        };
+
+        match piece {
+            Piece::WhiteKing | Piece::BlackKing => {
+                let color = (piece == Piece::BlackKing) as usize;
+
+                if kings[color] && !hexchess.variant.has_king_promotion() {
+                    return Err(format!("multiple {} kings", match color {
+                        0 => "white",
+                        _ => "black",
+                    }));
+                }
+
+                kings[color] = true;
+            },
+            _ => {},
+        };
+
+        hexchess.board[index] = Some(piece);
+        count += 1;
+    }
+
+    if hexchess.variant.has_drops() {
+        while count < MAX_PIECES {
+            let nibble = read_nibble(bytes, count);
+
+            if nibble == END_OF_POCKETS {
+                break;
+            }
+
+            let piece = match PIECES.get(nibble as usize) {
+                Some(piece) if drop_index(*piece).is_some() => *piece,
+                _ => return Err(format!("invalid piece in hand: {}", nibble)),
+            };
+
+            let color = match get_color(&piece) {
+                Color::White => 0,
+                Color::Black => 1,
+            };
+
+            hexchess.pockets[color][drop_index(piece).unwrap()] += 1;
+
+            count += 1;
+        }
+    }
+
+    hexchess.turn = match bytes[offset] & 0x0F {
+        0 => Color::White,
+        1 => Color::Black,
+        value => return Err(format!("invalid turn color: {}", value)),
+    };
+
+    hexchess.ep = match bytes[offset + 1] {
+        NO_EN_PASSANT => None,
+        ep => match hexchess.geometry().is_en_passant(ep, Color::White) || hexchess.geometry().is_en_passant(ep, Color::Black) {
+            true => Some(ep),
+            false => return Err(format!("illegal en passant position: {}", ep)),
+        },
+    };
+
+    hexchess.halfmove = bytes[offset + 2];
+
+    hexchess.fullmove = match u16::from_be_bytes([bytes[offset + 3], bytes[offset + 4]]) {
+        0 => return Err("invalid fullmove: 0".to_string()),
+        fullmove => fullmove,
+    };
+
+    Ok(hexchess)
+}
+
+/// read the piece nibble at an index
+fn read_nibble(bytes: &[u8], index: usize) -> u8 {
+    let byte = bytes[OCCUPANCY_BYTES + index / 2];
+
+    match index % 2 {
+        0 => byte >> 4,
+        _ => byte & 0x0F,
+    }
+}
+
+/// write the piece nibble at an index
+fn write_nibble(bytes: &mut [u8], index: usize, nibble: u8) {
+    bytes[OCCUPANCY_BYTES + index / 2] |= match index % 2 {
+        0 => nibble << 4,
+        _ => nibble,
+    };
+}
+
+/// Starting position and moves of a game
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct GameRecord {
+    /// Moves played from the starting position
+    #[cfg_attr(feature = "wasm", tsify(type = "SanStruct[]"))]
+    pub moves: Vec<San>,
+
+    /// Starting position
+    #[cfg_attr(feature = "wasm", tsify(type = "HexchessStruct"))]
+    pub start: Hexchess,
+}
+
+/// Encode a game as a version byte, a flags byte, an optional starting
+/// position, then each move as it's index in `current_moves`. Indexes that
+/// don't fit in a byte are written as 0xFF followed by a big-endian u16.
+pub fn encode_game(start: Option<&str>, moves: &[San]) -> Result<Vec<u8>, String> {
+    match start {
+        Some(fen) => encode_game_from(&Hexchess::parse(fen)?, moves),
+        None => encode_game_from(&Hexchess::init(), moves),
+    }
+}
+
+/// Encode a game from a starting position of any variant
+pub fn encode_game_from(start: &Hexchess, moves: &[San]) -> Result<Vec<u8>, String> {
+    let mut hexchess = *start;
+
+    let mut bytes: Vec<u8> = vec![GAME_VERSION];
+
+    match hexchess == Hexchess::init() {
+        true => bytes.push(0),
+        false => {
+            bytes.push(CUSTOM_START);
+            bytes.extend(encode_position(&hexchess)?);
+        },
+    };
+
+    for (ply, san) in moves.iter().enumerate() {
+        let index = match hexchess.current_moves().iter().position(|m| m == san) {
+            Some(index) => index,
+            None => return Err(format!("illegal move at ply {}: {}", ply + 1, san)),
+        };
+
+        match u8::try_from(index) {
+            Ok(index) if index < WIDE_INDEX => bytes.push(index),
+            _ => {
+                bytes.push(WIDE_INDEX);
+                bytes.extend((index as u16).to_be_bytes());
+            },
+        };
+
+        hexchess.apply_legal_move(san);
+    }
+
+    Ok(bytes)
+}
+
+/// Decode a game, replaying each move to verify it's legal
+pub fn decode_game(bytes: &[u8]) -> Result<GameRecord, String> {
+    let mut cursor = bytes.iter().copied();
+
+    match cursor.next() {
+        Some(GAME_VERSION) => {},
+        Some(version) => return Err(format!("unsupported game version: {}", version)),
+        None => return Err("missing game version".to_string()),
+    };
+
+    let start = match cursor.next() {
+        Some(0) => Hexchess::init(),
+        Some(CUSTOM_START) => {
+            let position: Vec<u8> = cursor.by_ref().take(POSITION_BYTES).collect();
+
+            decode_position(&position)?
+        },
+        Some(flags) => return Err(format!("invalid game flags: {}", flags)),
+        None => return Err("missing game flags".to_string()),
+    };
+
+    let mut hexchess = start;
+    let mut moves: Vec<San> = vec![];
+
+    while let Some(byte) = cursor.next() {
+        let index = match byte {
+            WIDE_INDEX => match (cursor.next(), cursor.next()) {
+                (Some(high), Some(low)) => u16::from_be_bytes([high, low]) as usize,
+                _ => return Err("truncated move index".to_string()),
+            },
+            _ => byte as usize,
+        };
+
+        let san = match hexchess.current_moves().get(index) {
+            Some(san) => *san,
+            None => return Err(format!("illegal move index at ply {}: {}", moves.len() + 1, index)),
+        };
+
+        hexchess.apply_legal_move(&san);
+        moves.push(san);
+    }
+
+    Ok(GameRecord { moves, start })
+}
+
+/// Encode a position as a url-safe share code
+pub fn to_share_code(hexchess: &Hexchess) -> Result<String, String> {
+    encode_position(hexchess).map(|bytes| base64_encode(&bytes))
+}
+
+/// Decode a position from a share code
+pub fn from_share_code(code: &str) -> Result<Hexchess, String> {
+    decode_position(&base64_decode(code)?)
+}
+
+/// encode bytes as unpadded url-safe base64
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut result = String::new();
+
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for i in 0..=chunk.len() {
+            result.push(BASE64[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+        }
+    }
+
+    result
+}
+
+/// decode unpadded url-safe base64
+fn base64_decode(source: &str) -> Result<Vec<u8>, String> {
+    let mut result: Vec<u8> = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in source.trim_end_matches('=').bytes() {
+        let value = match BASE64.iter().position(|b| *b == c) {
+            Some(value) => value as u32,
+            None => return Err(format!("invalid share code character: {}", c as char)),
+        };
+
+        buffer = buffer << 6 | value;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if bits >= 6 || buffer != 0 {
+        return Err("invalid share code length".to_string());
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::INITIAL_POSITION;
+    use crate::hexchess::utils::playout;
+    use crate::{h, s};
+    use super::*;
+
+    #[test]
+    fn san_round_trip() {
+        let mut hexchess = Hexchess::init();
+
+        for _ in 0..30 {
+            let moves = hexchess.current_moves();
+
+            for san in moves.iter() {
+                assert_eq!(decode_san(encode_san(san).unwrap()), Ok(*san));
+            }
+
+            hexchess.apply_move(&moves[moves.len() / 2]).unwrap();
+        }
+    }
+
+    #[test]
+    fn san_drops() {
+        for notation in ["P@f6", "N@a1", "Q@f11"] {
+            let san = s!(notation);
+
+            assert_eq!(decode_san(encode_san(&san).unwrap()), Ok(san));
+        }
+
+        assert!(encode_san(&San { from: 96, promotion: None, to: h!("f6") }).is_err());
+    }
+
+    #[test]
+    fn san_promotions() {
+        for notation in ["f10f11q", "e9f11r", "a5a6b", "b1a1n", "c2c1q", "k2l1r", "f10f11k", "k2l1k"] {
+            let san = Variant::Antichess.parse_san(notation).unwrap();
+            let encoded = encode_san(&san).unwrap();
+
+            assert!((encoded >> 2 & 0x7F) >= 91);
+            assert_eq!(decode_san(encoded), Ok(san));
+        }
+
+        assert_eq!(encode_san(&s!("f9f11q")), Err("invalid promotion: f9f11q".to_string()));
+    }
+
+    #[test]
+    fn san_layout() {
+        let encoded = encode_san(&San { from: h!("f5"), promotion: None, to: h!("f7") }).unwrap();
+
+        assert_eq!(encoded >> 9, h!("f5"));
+        assert_eq!(encoded >> 2 & 0x7F, h!("f7"));
+        assert_eq!(encoded & 0x03, 0);
+    }
+
+    #[test]
+    fn invalid_encoded_san() {
+        assert_eq!(decode_san(96 << 9), Err(format!("invalid encoded move: {}", 96 << 9)));
+        assert_eq!(decode_san(91 << 9 | 91 << 2), Err(format!("invalid encoded move: {}", 91 << 9 | 91 << 2))); // <- drops never promote
+        assert_eq!(decode_san(1), Err("invalid encoded move: 1".to_string()));
+        assert_eq!(decode_san(103 << 2), Err(format!("invalid encoded move: {}", 103 << 2)));
+        assert!(decode_san(91 << 2).is_err()); // <- nothing above f11
+    }
+
+    #[test]
+    fn position_round_trip() {
+        let positions = [
+            Hexchess::new(),
+            Hexchess::init(),
+            Hexchess::parse("b/qbk/n1b1n/r5r/ppppppppp/6P4/5P5/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 1").unwrap(),
+            Hexchess::parse("1/3/5/7/9/11/5k5/11/5Q5/11/5K5 b - 99 65535").unwrap(),
+            Hexchess::init_variant(Variant::McCooey),
+            Hexchess::parse_variant("b/qbk/rnbnr/ppppppp/9/11/11/5P5/4PBP4/2PPNBNP3/3RQBKRP2 b c2 0 1", Variant::McCooey).unwrap(),
+            Hexchess::init_variant(Variant::Shafran),
+            Hexchess::parse_variant("b/bpk/qp1pb/np3pn/rp5pr/p7p/9/9/PPPPPPPPP/RNQBBKBNR b Kq - 0 1", Variant::Shafran).unwrap(),
+            Hexchess::init_variant(Variant::Mini),
+            Hexchess::init_variant(Variant::Crazyhouse),
+            Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[PPQnp] b - 0 1", Variant::Crazyhouse).unwrap(),
+            Hexchess::parse_variant("Q~/q2/5/7/9/2k8/11/11/11/5K5/r~10[Pn] b - 0 1", Variant::Crazyhouse).unwrap(),
+            Hexchess::init_variant(Variant::KingOfTheHill),
+            Hexchess::parse_variant("k/3/5/7/9/5R5/11/11/11/11/5K5 b - 3+2 1 1", Variant::ThreeCheck).unwrap(),
+            Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/5k5/4K1K4 b - 0 1", Variant::Antichess).unwrap(),
+            Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/4q6 w - 0 2", Variant::Dark).unwrap(),
+        ];
+
+        for hexchess in positions {
+            let bytes = encode_position(&hexchess).unwrap();
+
+            assert_eq!(bytes.len(), POSITION_BYTES);
+            assert_eq!(decode_position(&bytes), Ok(hexchess));
+        }
+    }
+
+    #[test]
+    fn position_with_too_many_pieces() {
+        let hexchess = Hexchess::parse("p/ppp/ppppp/ppppppp/ppppppppp/ppppppppppp/ppppppppppp/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(encode_position(&hexchess), Err("too many pieces to encode: more than 36".to_string()));
+    }
+
+    #[test]
+    fn invalid_encoded_position() {
+        let bytes = encode_position(&Hexchess::init()).unwrap();
+
+        assert_eq!(decode_position(&bytes[1..]), Err("invalid encoded position length: 34".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES + PIECE_BYTES] = 2;
+        assert_eq!(decode_position(&invalid), Err("invalid turn color: 2".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES + PIECE_BYTES] = 0xF0;
+        assert_eq!(decode_position(&invalid), Err("invalid variant: 15".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES + PIECE_BYTES + 1] = h!("f1");
+        assert_eq!(decode_position(&invalid), Err(format!("illegal en passant position: {}", h!("f1"))));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES + PIECE_BYTES + 3] = 0;
+        invalid[OCCUPANCY_BYTES + PIECE_BYTES + 4] = 0;
+        assert_eq!(decode_position(&invalid), Err("invalid fullmove: 0".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES - 1] |= 0x01;
+        assert_eq!(decode_position(&invalid), Err("invalid occupancy".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES - 1] |= 0x10; // <- castling right in a variant without castling
+        assert_eq!(decode_position(&invalid), Err("invalid occupancy".to_string()));
+
+        let mut invalid = encode_position(&Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11 w - 0 1", Variant::Crazyhouse).unwrap()).unwrap();
+        invalid[OCCUPANCY_BYTES + 1] = 0x5F; // <- a king following the board's pieces
+        assert_eq!(decode_position(&invalid), Err("invalid piece in hand: 5".to_string()));
+
+        let mut invalid = bytes;
+        invalid[OCCUPANCY_BYTES] = 0xF0;
+        assert_eq!(decode_position(&invalid), Err("invalid piece: 15".to_string()));
+    }
+
+    #[test]
+    fn share_code() {
+        let mut hexchess = Hexchess::init();
+        hexchess.apply("g4g6 f7f6").unwrap();
+
+        let code = to_share_code(&hexchess).unwrap();
+
+        assert_eq!(code.len(), 47);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(from_share_code(&code), Ok(hexchess));
+        assert_eq!(from_share_code("abc!"), Err("invalid share code character: !".to_string()));
+    }
+
+    #[test]
+    fn game_round_trip() {
+        let moves = playout(7, 80);
+        let bytes = encode_game(None, &moves).unwrap();
+
+        assert_eq!(bytes.len(), 2 + moves.len());
+        assert_eq!(decode_game(&bytes), Ok(GameRecord { moves, start: Hexchess::init() }));
+    }
+
+    #[test]
+    fn game_with_custom_start() {
+        let fen = "1/3/5/7/9/11/5k5/11/5Q5/11/5K5 b - 0 1";
+        let moves = vec![s!("f5e6"), s!("f3f4")];
+        let bytes = encode_game(Some(fen), &moves).unwrap();
+
+        assert_eq!(bytes.len(), 2 + POSITION_BYTES + 2);
+        assert_eq!(bytes[1], CUSTOM_START);
+        assert_eq!(decode_game(&bytes), Ok(GameRecord { moves, start: Hexchess::parse(fen).unwrap() }));
+
+        // initial position is never stored
+        let bytes = encode_game(Some(INITIAL_POSITION), &[s!("g4g6")]).unwrap();
+        assert_eq!(bytes.len(), 3);
+    }
+
+    #[test]
+    fn game_with_variant() {
+        let start = Hexchess::init_variant(Variant::McCooey);
+        let moves = vec![s!("c1c3"), s!("f8f7")];
+        let bytes = encode_game_from(&start, &moves).unwrap();
+
+        assert_eq!(bytes[1], CUSTOM_START);
+        assert_eq!(decode_game(&bytes), Ok(GameRecord { moves, start }));
+
+        let start = Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[Rr] w - 0 1", Variant::Crazyhouse).unwrap();
+        let moves = vec![s!("R@f7"), s!("R@e1"), s!("f2e1")];
+        let bytes = encode_game_from(&start, &moves).unwrap();
+
+        assert_eq!(decode_game(&bytes), Ok(GameRecord { moves, start }));
+
+        let start = Hexchess::parse_variant("k/3/5/7/9/9/9/9/9/R4K2R w KQ - 0 1", Variant::Shafran).unwrap();
+        let moves = vec![s!("g2k2")]; // <- castling, as the king moving onto it's rook
+        let bytes = encode_game_from(&start, &moves).unwrap();
+
+        assert_eq!(decode_game(&bytes), Ok(GameRecord { moves, start }));
+    }
+
+    #[test]
+    fn game_with_illegal_move() {
+        assert_eq!(
+            encode_game(None, &[s!("g4g6"), s!("g6g7")]),
+            Err("illegal move at ply 2: g6g7".to_string()),
+        );
+
+        assert_eq!(encode_game(Some("whoops"), &[]), Err("invalid character at index 0: w".to_string()));
+    }
+
+    #[test]
+    fn wide_move_index() {
+        let narrow = decode_game(&[GAME_VERSION, 0, 5]).unwrap();
+        let wide = decode_game(&[GAME_VERSION, 0, WIDE_INDEX, 0, 5]).unwrap();
+
+        assert_eq!(narrow, wide);
+    }
+
+    #[test]
+    fn invalid_encoded_game() {
+        assert_eq!(decode_game(&[]), Err("missing game version".to_string()));
+        assert_eq!(decode_game(&[9]), Err("unsupported game version: 9".to_string()));
+        assert_eq!(decode_game(&[GAME_VERSION]), Err("missing game flags".to_string()));
+        assert_eq!(decode_game(&[GAME_VERSION, 4]), Err("invalid game flags: 4".to_string()));
+        assert_eq!(decode_game(&[GAME_VERSION, CUSTOM_START, 0]), Err("invalid encoded position length: 1".to_string()));
+        assert_eq!(decode_game(&[GAME_VERSION, 0, WIDE_INDEX, 0]), Err("truncated move index".to_string()));
+        assert_eq!(decode_game(&[GAME_VERSION, 0, 200]), Err("illegal move index at ply 1: 200".to_string()));
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg");
+        assert_eq!(base64_encode(b"fo"), "Zm8");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg");
+        assert_eq!(base64_encode(&[0xFB, 0xFF]), "-_8");
+
+        for source in ["", "Zg", "Zm8", "Zm9v", "Zm9vYg", "-_8", "Zg=="] {
+            let decoded = base64_decode(source).unwrap();
+
+            assert_eq!(base64_encode(&decoded), source.trim_end_matches('='));
+        }
+
+        assert_eq!(base64_decode("Z"), Err("invalid share code length".to_string()));
+        assert_eq!(base64_decode("Zh"), Err("invalid share code length".to_string()));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::constants::Color;
+use crate::hexchess::hexchess::Hexchess;
+use crate::hexchess::san::San;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify_next::Tsify;
+
+/// State of a game after the last move
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Status {
+    /// The current turn is in check and has no legal moves
+    Checkmate,
+
+    /// A king has been captured, in variants without check
+    KingCapture,
+
+    /// A king has reached the hill in the center of the board
+    KingOfTheHill,
+
+    /// The current turn has legal moves
+    Ongoing,
+
+    /// The current turn is not in check, but has no legal moves
+    Stalemate,
+
+    /// A color has given the variant's limit of checks
+    ThreeCheck,
+}
+
+/// Position along with the moves that led to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    /// Current position
+    pub hexchess: Hexchess,
+
+    /// Moves played, each paired with the position it was played from
+    history: Vec<(San, Hexchess)>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::init()
+    }
+}
+
+impl Game {
+    pub fn new(hexchess: Hexchess) -> Self {
+        Self {
+            hexchess,
+            history: vec![],
+        }
+    }
+
+    /// apply a whitespace separated sequence of moves, leaving the game unchanged on error
+    pub fn apply(&mut self, sequence: &str) -> Result<(), String> {
+        let mut clone = self.clone();
+
+        for (i, part) in sequence.split_whitespace().enumerate() {
+            let san = match clone.hexchess.variant.parse_san(part) {
+                Ok(san) => san,
+                Err(_) => return Err(format!("invalid san at index {}: {}", i, part)),
+            };
+
+            if clone.apply_move(&san).is_err() {
+                return Err(format!("illegal move at index {}: {}", i, part));
+            }
+        }
+
+        *self = clone;
+
+        Ok(())
+    }
+
+    /// apply a legal move and record it in the history
+    pub fn apply_move(&mut self, san: &San) -> Result<(), String> {
+        // checkmate and stalemate leave no legal moves, but a variant's goal does not
+        if self.hexchess.variant_winner().is_some() {
+            return Err("game is over".to_string());
+        }
+
+        let before = self.hexchess;
+
+        self.hexchess.apply_move(san)?;
+        self.history.push((*san, before));
+
+        Ok(())
+    }
+
+    /// get the moves played, in order
+    pub fn history(&self) -> Vec<San> {
+        self.history.iter().map(|(san, _)| *san).collect()
+    }
+
+    /// create game at the initial position
+    pub fn init() -> Self {
+        Self::new(Hexchess::init())
+    }
+
+    /// parse game starting from a fen position
+    pub fn parse(source: &str) -> Result<Self, String> {
+        Ok(Self::new(Hexchess::parse(source)?))
+    }
+
+    /// get the status of the current position
+    pub fn status(&self) -> Status {
+        if self.hexchess.variant_winner().is_some() {
+            let variant = self.hexchess.variant;
+
+            return match (variant.check_limit(), variant.has_king_capture()) {
+                (Some(_), _) => Status::ThreeCheck,
+                (None, true) => Status::KingCapture,
+                (None, false) => Status::KingOfTheHill,
+            };
+        }
+
+        if !self.hexchess.current_moves().is_empty() {
+            return Status::Ongoing;
+        }
+
+        match self.hexchess.is_check() {
+            true => Status::Checkmate,
+            false => Status::Stalemate,
+        }
+    }
+
+    /// get the color that has won the game, if any
+    pub fn winner(&self) -> Option<Color> {
+        match self.status() {
+            Status::Checkmate => Some(match self.hexchess.turn {
+                Color::Black => Color::White,
+                Color::White => Color::Black,
+            }),
+            Status::KingCapture | Status::KingOfTheHill | Status::ThreeCheck => self.hexchess.variant_winner(),
+            Status::Ongoing => None,
+
+            // a player without legal moves, including one with no pieces left, wins at antichess
+            Status::Stalemate => match self.hexchess.variant.has_forced_captures() {
+                true => Some(self.hexchess.turn),
+                false => None,
+            },
+        }
+    }
+
+    /// take back the last move, returning it
+    pub fn undo(&mut self) -> Option<San> {
+        let (san, before) = self.history.pop()?;
+
+        self.hexchess = before;
+
+        Some(san)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hexchess::variant::Variant;
+    use crate::s;
+    use super::*;
+
+    #[test]
+    fn apply_and_undo() {
+        let mut game = Game::init();
+
+        game.apply("g4g6 f7g6 f5f7").unwrap();
+
+        assert_eq!(game.history(), vec![s!("g4g6"), s!("f7g6"), s!("f5f7")]);
+
+        assert_eq!(game.undo(), Some(s!("f5f7")));
+        assert_eq!(game.hexchess.to_string(), "b/qbk/n1b1n/r5r/pppp1pppp/6p4/5P5/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 2");
+
+        assert_eq!(game.undo(), Some(s!("f7g6")));
+        assert_eq!(game.undo(), Some(s!("g4g6")));
+        assert_eq!(game.undo(), None);
+        assert_eq!(game, Game::init());
+    }
+
+    #[test]
+    fn apply_is_atomic() {
+        let mut game = Game::init();
+
+        assert_eq!(game.apply("g4g6 a1a2"), Err("illegal move at index 1: a1a2".to_string()));
+        assert_eq!(game.apply("g4g6 whoops"), Err("invalid san at index 1: whoops".to_string()));
+        assert_eq!(game, Game::init());
+    }
+
+    #[test]
+    fn illegal_moves_are_not_recorded() {
+        let mut game = Game::init();
+
+        assert!(game.apply_move(&s!("f7f6")).is_err());
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn status() {
+        assert_eq!(Game::init().status(), Status::Ongoing);
+
+        let mut checkmate = Game::parse("K/3/5/3q3/2q6/11/11/11/11/11/11 b - 0 1").unwrap();
+        checkmate.apply("d7f9").unwrap();
+        assert_eq!(checkmate.status(), Status::Checkmate);
+
+        let mut stalemate = Game::parse("k/1P1/5/3K3/9/11/11/11/11/11/11 w - 0 1").unwrap();
+        stalemate.apply("f8f9").unwrap();
+        assert_eq!(stalemate.status(), Status::Stalemate);
+    }
+
+    #[test]
+    fn variant_status() {
+        let mut hill = Game::new(Hexchess::parse_variant("1/1k1/5/7/9/11/11/5K5/11/11/11 w - 0 1", Variant::KingOfTheHill).unwrap());
+        hill.apply("f4f5").unwrap();
+        assert_eq!(hill.status(), Status::KingOfTheHill);
+        assert_eq!(hill.winner(), Some(Color::White));
+        assert_eq!(hill.apply("e9e8"), Err("illegal move at index 0: e9e8".to_string()));
+        assert_eq!(hill.apply_move(&s!("e9e8")), Err("game is over".to_string()));
+
+        let mut checks = Game::new(Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/R4K5 w - 2+0 0 1", Variant::ThreeCheck).unwrap());
+        checks.apply("a1a6").unwrap();
+        assert_eq!(checks.status(), Status::ThreeCheck);
+        assert_eq!(checks.winner(), Some(Color::White));
+
+        let mut antichess = Game::new(Hexchess::parse_variant("1/3/5/7/9/11/11/11/11/5k5/4K6 b - 0 1", Variant::Antichess).unwrap());
+        antichess.apply("f2e1").unwrap();
+        assert_eq!(antichess.status(), Status::Stalemate);
+        assert_eq!(antichess.winner(), Some(Color::White)); // <- white has lost every piece
+
+        let mut dark = Game::new(Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/5q5/5K5 w - 0 1", Variant::Dark).unwrap());
+        dark.apply("f1e1 f2e1").unwrap();
+        assert_eq!(dark.status(), Status::KingCapture);
+        assert_eq!(dark.winner(), Some(Color::Black));
+        assert_eq!(dark.apply_move(&s!("f11e10")), Err("game is over".to_string()));
+    }
+}
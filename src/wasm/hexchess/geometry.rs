@@ -0,0 +1,495 @@
+use std::fmt;
+
+use crate::hexchess::utils::{
+    index,
+    step,
+    to_position,
+};
+
+/// Directions along the hexboard graph, clockwise from 12 o'clock. Even
+/// directions are orthogonal, and odd directions are diagonal.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Direction {
+    North = 0,
+    NorthNorthEast = 1,
+    NorthEast = 2,
+    East = 3,
+    SouthEast = 4,
+    SouthSouthEast = 5,
+    South = 6,
+    SouthSouthWest = 7,
+    SouthWest = 8,
+    West = 9,
+    NorthWest = 10,
+    NorthNorthWest = 11,
+}
+
+impl Direction {
+    /// All directions, clockwise from 12 o'clock
+    pub const ALL: [Direction; 12] = [
+        Direction::North,
+        Direction::NorthNorthEast,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthSouthEast,
+        Direction::South,
+        Direction::SouthSouthWest,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthNorthWest,
+    ];
+
+    /// Diagonal directions, the way bishops move
+    pub const DIAGONAL: [Direction; 6] = [
+        Direction::NorthNorthEast,
+        Direction::East,
+        Direction::SouthSouthEast,
+        Direction::SouthSouthWest,
+        Direction::West,
+        Direction::NorthNorthWest,
+    ];
+
+    /// Orthogonal directions, the way rooks move
+    pub const ORTHOGONAL: [Direction; 6] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::NorthWest,
+    ];
+
+    /// create direction from a graph index, 0..12
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// get the graph index of this direction, 0..12
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// test if direction is diagonal
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// test if direction is orthogonal
+    pub fn is_orthogonal(&self) -> bool {
+        !self.is_diagonal()
+    }
+
+    /// reflect direction across the f-file
+    pub fn mirror(&self) -> Self {
+        Self::ALL[((12 - self.index()) % 12) as usize]
+    }
+
+    /// get the axial (q, r) offset of a single step in this direction
+    pub fn offset(&self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthNorthEast => (1, -2),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (2, -1),
+            Direction::SouthEast => (1, 0),
+            Direction::SouthSouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthSouthWest => (-1, 2),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-2, 1),
+            Direction::NorthWest => (-1, 0),
+            Direction::NorthNorthWest => (-1, -1),
+        }
+    }
+
+    /// get the direction pointing the opposite way
+    pub fn opposite(&self) -> Self {
+        self.rotate(6)
+    }
+
+    /// rotate clockwise by a number of 30 degree steps, negative steps rotate counter-clockwise
+    pub fn rotate(&self, steps: i8) -> Self {
+        Self::ALL[(self.index() as i16 + steps as i16).rem_euclid(12) as usize]
+    }
+}
+
+impl From<Direction> for u8 {
+    fn from(direction: Direction) -> u8 {
+        direction.index()
+    }
+}
+
+/// A position on the hexboard, identified by it's fen index.
+///
+/// Cells can also be addressed with axial (q, r) coordinates centered on f6,
+/// where q increases with each file to the right and r increases going down
+/// towards white. Cube coordinates are (q, -q - r, r), and the board is every
+/// cell within 5 steps of the center.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cell(u8);
+
+impl Cell {
+    /// create cell from a fen index, 0..91
+    pub fn new(index: u8) -> Option<Self> {
+        match index < 91 {
+            true => Some(Self(index)),
+            false => None,
+        }
+    }
+
+    /// iterate over all cells in fen order
+    pub fn all() -> impl Iterator<Item = Cell> {
+        (0u8..91u8).map(Cell)
+    }
+
+    /// get axial (q, r) coordinates
+    pub fn axial(&self) -> (i8, i8) {
+        let position = self.position();
+        let file = position.chars().next().unwrap();
+        let rank: i8 = position[1..].parse().unwrap();
+        let q = FILES.iter().position(|f| *f == file).unwrap() as i8 - 5;
+
+        (q, 6 - rank - q.max(0))
+    }
+
+    /// get cells strictly between two cells, or nothing if they do not share a line
+    pub fn between(a: Cell, b: Cell) -> Vec<Cell> {
+        let direction = match a.direction_to(b) {
+            Some(direction) => direction,
+            None => return vec![],
+        };
+
+        a.ray(direction)
+            .into_iter()
+            .take_while(|cell| *cell != b)
+            .collect()
+    }
+
+    /// get cube (x, y, z) coordinates
+    pub fn cube(&self) -> (i8, i8, i8) {
+        let (q, r) = self.axial();
+
+        (q, -q - r, r)
+    }
+
+    /// get the direction of a straight line from this cell to another
+    pub fn direction_to(&self, other: Cell) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| self.ray(*direction).contains(&other))
+    }
+
+    /// count the fewest orthogonal steps between two cells
+    pub fn distance(&self, other: Cell) -> u8 {
+        let (x1, y1, z1) = self.cube();
+        let (x2, y2, z2) = other.cube();
+
+        ((x1 - x2).abs() + (y1 - y2).abs() + (z1 - z2).abs()) as u8 / 2
+    }
+
+    /// create cell from axial (q, r) coordinates
+    pub fn from_axial(q: i8, r: i8) -> Option<Self> {
+        if q.abs().max(r.abs()).max((q + r).abs()) > 5 {
+            return None;
+        }
+
+        let file = FILES[(q + 5) as usize];
+        let rank = 6 - r - q.max(0);
+
+        Self::from_position(&format!("{}{}", file, rank))
+    }
+
+    /// create cell from cube (x, y, z) coordinates
+    pub fn from_cube(x: i8, y: i8, z: i8) -> Option<Self> {
+        match x + y + z == 0 {
+            true => Self::from_axial(x, z),
+            false => None,
+        }
+    }
+
+    /// create cell from a position name
+    pub fn from_position(position: &str) -> Option<Self> {
+        index(position).ok().map(Cell)
+    }
+
+    /// get the fen index of this cell
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// get the full line through two cells, edge to edge, or nothing if they do not share a line
+    pub fn line_through(a: Cell, b: Cell) -> Vec<Cell> {
+        let direction = match a.direction_to(b) {
+            Some(direction) => direction,
+            None => return vec![],
+        };
+
+        let mut result: Vec<Cell> = a.ray(direction.opposite());
+
+        result.reverse();
+        result.push(a);
+        result.extend(a.ray(direction));
+
+        result
+    }
+
+    /// reflect cell across the f-file
+    pub fn mirror(&self) -> Cell {
+        let (q, r) = self.axial();
+
+        Self::from_axial(-q, r + q).unwrap()
+    }
+
+    /// get orthogonally adjacent cells, clockwise from 12 o'clock
+    pub fn neighbors(&self) -> Vec<Cell> {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(|direction| self.step(direction))
+            .collect()
+    }
+
+    /// get the position name of this cell
+    pub fn position(&self) -> &'static str {
+        to_position(&self.0)
+    }
+
+    /// walk in a direction until the edge of the board
+    pub fn ray(&self, direction: Direction) -> Vec<Cell> {
+        let mut result: Vec<Cell> = vec![];
+        let mut cell = *self;
+
+        while let Some(next) = cell.step(direction) {
+            result.push(next);
+            cell = next;
+        }
+
+        result
+    }
+
+    /// rotate clockwise around f6 by a number of 60 degree steps
+    pub fn rotate(&self, steps: i8) -> Cell {
+        let (mut x, mut y, mut z) = self.cube();
+
+        for _ in 0..steps.rem_euclid(6) {
+            (x, y, z) = (-z, -x, -y);
+        }
+
+        Self::from_cube(x, y, z).unwrap()
+    }
+
+    /// step to the adjacent cell in a direction
+    pub fn step(&self, direction: Direction) -> Option<Cell> {
+        step(self.0, direction.index()).map(Cell)
+    }
+}
+
+/// File names from left to right
+pub const FILES: [char; 11] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'k', 'l'];
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.position())
+    }
+}
+
+impl From<Cell> for u8 {
+    fn from(cell: Cell) -> u8 {
+        cell.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::h;
+    use super::*;
+
+    fn cell(position: &str) -> Cell {
+        Cell::from_position(position).unwrap()
+    }
+
+    fn positions(cells: Vec<Cell>) -> Vec<&'static str> {
+        cells.iter().map(|cell| cell.position()).collect()
+    }
+
+    mod direction {
+        use super::*;
+
+        #[test]
+        fn from_index() {
+            assert_eq!(Direction::from_index(0), Some(Direction::North));
+            assert_eq!(Direction::from_index(11), Some(Direction::NorthNorthWest));
+            assert_eq!(Direction::from_index(12), None);
+        }
+
+        #[test]
+        fn diagonal_and_orthogonal() {
+            assert!(Direction::ORTHOGONAL.iter().all(|d| d.is_orthogonal()));
+            assert!(Direction::DIAGONAL.iter().all(|d| d.is_diagonal()));
+        }
+
+        #[test]
+        fn mirror() {
+            assert_eq!(Direction::North.mirror(), Direction::North);
+            assert_eq!(Direction::East.mirror(), Direction::West);
+            assert_eq!(Direction::NorthEast.mirror(), Direction::NorthWest);
+            assert_eq!(Direction::SouthSouthEast.mirror(), Direction::SouthSouthWest);
+            assert_eq!(Direction::South.mirror(), Direction::South);
+        }
+
+        #[test]
+        fn opposite() {
+            for direction in Direction::ALL {
+                assert_eq!(direction.opposite().opposite(), direction);
+                assert_eq!(direction.opposite().is_diagonal(), direction.is_diagonal());
+            }
+
+            assert_eq!(Direction::North.opposite(), Direction::South);
+            assert_eq!(Direction::East.opposite(), Direction::West);
+        }
+
+        #[test]
+        fn rotate() {
+            assert_eq!(Direction::North.rotate(1), Direction::NorthNorthEast);
+            assert_eq!(Direction::North.rotate(-1), Direction::NorthNorthWest);
+            assert_eq!(Direction::NorthWest.rotate(4), Direction::NorthEast);
+            assert_eq!(Direction::East.rotate(24), Direction::East);
+        }
+    }
+
+    #[test]
+    fn axial_and_cube() {
+        assert_eq!(cell("f6").axial(), (0, 0));
+        assert_eq!(cell("f11").axial(), (0, -5));
+        assert_eq!(cell("a1").axial(), (-5, 5));
+        assert_eq!(cell("l1").axial(), (5, 0));
+        assert_eq!(cell("l6").axial(), (5, -5));
+        assert_eq!(cell("a1").cube(), (-5, 0, 5));
+
+        for c in Cell::all() {
+            let (q, r) = c.axial();
+            let (x, y, z) = c.cube();
+
+            assert_eq!(Cell::from_axial(q, r), Some(c));
+            assert_eq!(Cell::from_cube(x, y, z), Some(c));
+        }
+
+        assert_eq!(Cell::from_axial(0, 6), None);
+        assert_eq!(Cell::from_axial(5, 1), None);
+        assert_eq!(Cell::from_cube(0, 0, 1), None);
+    }
+
+    #[test]
+    fn graph_matches_coordinates() {
+        for c in Cell::all() {
+            let (q, r) = c.axial();
+
+            for direction in Direction::ALL {
+                let (dq, dr) = direction.offset();
+
+                assert_eq!(c.step(direction), Cell::from_axial(q + dq, r + dr));
+            }
+        }
+    }
+
+    #[test]
+    fn mirror() {
+        assert_eq!(cell("f6").mirror(), cell("f6"));
+        assert_eq!(cell("a1").mirror(), cell("l1"));
+        assert_eq!(cell("c2").mirror(), cell("i2"));
+        assert_eq!(cell("e10").mirror(), cell("g10"));
+
+        for c in Cell::all() {
+            assert_eq!(c.mirror().mirror(), c);
+
+            for direction in Direction::ALL {
+                assert_eq!(c.step(direction).map(|n| n.mirror()), c.mirror().step(direction.mirror()));
+            }
+        }
+    }
+
+    #[test]
+    fn rotate() {
+        assert_eq!(cell("f11").rotate(1), cell("l6"));
+        assert_eq!(cell("f11").rotate(3), cell("f1"));
+        assert_eq!(cell("f11").rotate(-1), cell("a6"));
+        assert_eq!(cell("f6").rotate(2), cell("f6"));
+
+        for c in Cell::all() {
+            assert_eq!(c.rotate(6), c);
+            assert_eq!(c.distance(cell("f6")), c.rotate(1).distance(cell("f6")));
+
+            for direction in Direction::ALL {
+                assert_eq!(c.step(direction).map(|n| n.rotate(1)), c.rotate(1).step(direction.rotate(2)));
+            }
+        }
+    }
+
+    #[test]
+    fn between() {
+        assert_eq!(positions(Cell::between(cell("f1"), cell("f6"))), ["f2", "f3", "f4", "f5"]);
+        assert_eq!(positions(Cell::between(cell("a1"), cell("d7"))), ["b3", "c5"]);
+        assert_eq!(positions(Cell::between(cell("f6"), cell("f5"))).len(), 0);
+        assert_eq!(positions(Cell::between(cell("f6"), cell("g8"))).len(), 0);
+    }
+
+    #[test]
+    fn direction_to() {
+        assert_eq!(cell("f1").direction_to(cell("f11")), Some(Direction::North));
+        assert_eq!(cell("a1").direction_to(cell("l1")), Some(Direction::East));
+        assert_eq!(cell("f6").direction_to(cell("g8")), None);
+        assert_eq!(cell("f6").direction_to(cell("f6")), None);
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(cell("f6").distance(cell("f6")), 0);
+        assert_eq!(cell("f6").distance(cell("f7")), 1);
+        assert_eq!(cell("f6").distance(cell("g8")), 3);
+        assert_eq!(cell("a1").distance(cell("l1")), 10);
+        assert_eq!(cell("f1").distance(cell("f11")), 10);
+        assert_eq!(cell("a6").distance(cell("l1")), 10);
+    }
+
+    #[test]
+    fn from_position() {
+        assert_eq!(Cell::from_position("f6"), Cell::new(h!("f6")));
+        assert_eq!(Cell::from_position("whoops"), None);
+        assert_eq!(Cell::new(91), None);
+        assert_eq!(Cell::all().count(), 91);
+    }
+
+    #[test]
+    fn line_through() {
+        assert_eq!(
+            positions(Cell::line_through(cell("f5"), cell("f7"))),
+            ["f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10", "f11"],
+        );
+
+        assert_eq!(
+            positions(Cell::line_through(cell("c2"), cell("b1"))),
+            ["l5", "k5", "i5", "h5", "g5", "f5", "e4", "d3", "c2", "b1"],
+        );
+
+        assert_eq!(Cell::line_through(cell("f6"), cell("g8")).len(), 0);
+    }
+
+    #[test]
+    fn neighbors() {
+        assert_eq!(positions(cell("f6").neighbors()), ["f7", "g6", "g5", "f5", "e5", "e6"]);
+        assert_eq!(positions(cell("a1").neighbors()), ["a2", "b2", "b1"]);
+    }
+
+    #[test]
+    fn ray() {
+        assert_eq!(positions(cell("f6").ray(Direction::East)), ["h5", "k4"]);
+        assert_eq!(positions(cell("l1").ray(Direction::SouthEast)).len(), 0);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(cell("f11").to_string(), "f11");
+        assert_eq!(u8::from(cell("f11")), 0);
+    }
+}
@@ -1,37 +1,49 @@
-use crate::h;
-use crate::hexchess::pieces::king::king_moves_unsafe;
-use crate::hexchess::pieces::knight::knight_moves_unsafe;
-use crate::hexchess::pieces::pawn::pawn_moves_unsafe;
-use crate::hexchess::pieces::straight_line::straight_line_moves_unsafe;
-use crate::hexchess::san::San;
-use serde_with::serde_as;
+use crate::hexchess::board::Board;
+use crate::hexchess::pieces::king::{castling_moves, king_attacks, king_moves_unsafe};
+use crate::hexchess::pieces::knight::{knight_attacks, knight_moves_unsafe};
+use crate::hexchess::pieces::pawn::{pawn_attacks, pawn_moves_unsafe};
+use crate::hexchess::pieces::straight_line::{straight_line_attacks, straight_line_moves_unsafe};
+use crate::hexchess::geometry::{Cell, Direction};
+use crate::hexchess::san::{drop_index, San, DROP_PIECES};
+use crate::hexchess::text::{render_text, TextOptions};
+use crate::hexchess::variant::{BoardGeometry, CASTLING_RIGHTS, CellSet, side, Variant};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
 use tsify_next::Tsify;
-use wasm_bindgen::prelude::*;
 
 use crate::constants::{
     Color,
-    INITIAL_POSITION,
     Piece,
     PromotionPiece,
 };
 
 use crate::hexchess::utils::{
     get_color,
-    is_legal_en_passant,
-    step,
-    index,
-    to_position,
+    slides_along,
+    swap_color,
 };
 
 /// Hexchess game state
-#[serde_as]
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi, type_suffix = "Struct")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HexchessFields"))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi, type_suffix = "Struct"))]
 pub struct Hexchess {
-    #[tsify(type = "Board")]
-    #[serde_as(as = "[_; 91]")]
-    pub board: [Option<Piece>; 91],
+    #[cfg_attr(feature = "wasm", tsify(type = "VariantBoard"))]
+    pub board: Board,
+
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub castling: [bool; 4],
+
+    /// Checks given when the variant has a check limit, white then black
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub checks: [u8; 2],
 
     pub ep: Option<u8>,
 
@@ -39,8 +51,67 @@ pub struct Hexchess {
 
     pub halfmove: u8,
 
-    #[tsify(type = "Color")]
+    /// Pieces in hand when the variant has drops, white then black, counted in drop order
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub pockets: [[u8; 5]; 2],
+
+    /// Positions of promoted pieces when the variant has drops, these return to hand as pawns when captured
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    #[cfg_attr(feature = "wasm", tsify(optional, type = "number[]"))]
+    pub promoted: CellSet,
+
+    #[cfg_attr(feature = "wasm", tsify(type = "Color"))]
     pub turn: Color,
+
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub variant: Variant,
+}
+
+/// Deserialized fields of a game, checked before they become a `Hexchess`
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HexchessFields {
+    board: Board,
+
+    #[serde(default)]
+    castling: [bool; 4],
+
+    #[serde(default)]
+    checks: [u8; 2],
+
+    ep: Option<u8>,
+
+    fullmove: u16,
+
+    halfmove: u8,
+
+    #[serde(default)]
+    pockets: [[u8; 5]; 2],
+
+    #[serde(default)]
+    promoted: CellSet,
+
+    turn: Color,
+
+    #[serde(default)]
+    variant: Variant,
+}
+
+/// A piece standing alone between a king and a hostile slider
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Pin {
+    /// Direction from the king towards the pinner, 0..12
+    pub direction: u8,
+
+    /// Position index of the pinned piece
+    pub pinned: u8,
+
+    /// Position index of the hostile slider
+    pub pinner: u8,
 }
 
 impl Hexchess {
@@ -50,7 +121,7 @@ impl Hexchess {
         let mut i: u32 = 0;
 
         for part in sequence.split_whitespace() {
-            let san = match San::from(&part.to_string()) {
+            let san = match self.variant.parse_san(part) {
                 Ok(san) => san,
                 Err(_) => {
                     return Err(format!("invalid san at index {}: {}", i, part));
@@ -64,11 +135,7 @@ impl Hexchess {
             i += 1;
         }
 
-        self.board = clone.board;
-        self.turn = clone.turn;
-        self.ep = clone.ep;
-        self.fullmove = clone.fullmove;
-        self.halfmove = clone.halfmove;
+        *self = clone;
 
         Ok(())
     }
@@ -79,20 +146,102 @@ impl Hexchess {
             return Err(format!("illegal move: {:?}", san));
         }
 
-        self.apply_move_unsafe(san);
+        self.apply_legal_move(san);
 
         Ok(())
     }
 
+    /// apply a move known to be legal, counting checks given when the variant has a check limit
+    pub(crate) fn apply_legal_move(&mut self, san: &San) {
+        self.apply_move_unsafe(san);
+
+        let color = match self.turn {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        };
+
+        if self.variant.check_limit().is_some_and(|limit| self.checks[side(color)] < limit) && self.is_check() {
+            self.checks[side(color)] += 1;
+        }
+    }
+
     /// apply move, regardless of turn or legality
     pub fn apply_move_unsafe(&mut self, san: &San) -> &Self {
+        match self.try_apply_move_unsafe(san) {
+            Ok(_) => self,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// apply move regardless of turn or legality, failing on invalid positions
+    pub fn try_apply_move_unsafe(&mut self, san: &San) -> Result<&Self, String> {
+        if let Some(piece) = san.dropped().filter(|_| self.variant.has_drops()) {
+            return self.try_apply_drop_unsafe(piece, san.to);
+        }
+
+        if !self.geometry().contains(san.from) {
+            return Err(format!("invalid position index: {}", san.from));
+        }
+
+        if !self.geometry().contains(san.to) {
+            return Err(format!("invalid position index: {}", san.to));
+        }
+
+        if san.promotion == Some(PromotionPiece::King) && !self.variant.has_king_promotion() {
+            return Err(format!("cannot promote to king in variant: {}", self.variant));
+        }
+
         let piece = match self.board[san.from as usize] {
             Some(piece) => piece,
-            None => panic!("cannot apply move from empty position: {}", san.from),
+            None => return Err(format!("cannot apply move from empty position: {}", san.from)),
         };
 
+        let color = get_color(&piece);
+        let geometry = self.geometry();
+
+        // castle, the king moves onto it's own rook
+        let castle = geometry.castling.iter().flatten().find(|castling| {
+            castling.king == san.from &&
+            castling.rook == san.to &&
+            self.board[san.to as usize].is_some_and(|rook| get_color(&rook) == color)
+        });
+
+        if let Some(castling) = castle {
+            let rook = self.board[san.to as usize];
+
+            self.board[san.from as usize] = None;
+            self.board[san.to as usize] = None;
+            self.board[castling.king_to as usize] = Some(piece);
+            self.board[castling.rook_to as usize] = rook;
+        }
+
+        // revoke castling rights of moved or captured pieces
+        for (i, castling) in geometry.castling.iter().enumerate() {
+            if let Some(castling) = castling {
+                if [castling.king, castling.rook].iter().any(|n| *n == san.from || *n == san.to) {
+                    self.castling[i] = false;
+                }
+            }
+        }
+
+        // captured pieces go to the capturer's hand
+        if castle.is_none() && self.variant.has_drops() {
+            let captured = match self.board[san.to as usize] {
+                Some(_) if self.promoted.contains(san.to) => Some(Piece::WhitePawn), // <- promoted pieces return to hand as pawns
+                Some(captured) => Some(captured),
+                None if Some(san.to) == self.ep && (piece == Piece::BlackPawn || piece == Piece::WhitePawn) => Some(Piece::WhitePawn),
+                None => None,
+            };
+
+            if let Some(i) = captured.and_then(drop_index) {
+                self.pockets[side(color)][i] += 1;
+            }
+        }
+
         // update halfmove
-        if self.board[san.to as usize].is_some() || (
+        if castle.is_some() {
+            self.halfmove += 1;
+        } else if self.board[san.to as usize].is_some() || (
             piece == Piece::BlackPawn ||
             piece == Piece::WhitePawn
         ) {
@@ -101,8 +250,6 @@ impl Hexchess {
             self.halfmove += 1;
         }
 
-        let color = get_color(&piece);
-
         // update fullmove and turn color
         if color == Color::Black {
             self.fullmove += 1;
@@ -111,6 +258,12 @@ impl Hexchess {
             self.turn = Color::Black;
         }
 
+        if castle.is_some() {
+            self.ep = None;
+
+            return Ok(self);
+        }
+
         // set from positions
         self.board[san.from as usize] = None;
 
@@ -121,12 +274,14 @@ impl Hexchess {
                 Some(piece) => match color {
                     Color::Black => match piece {
                         PromotionPiece::Bishop => Piece::BlackBishop,
+                        PromotionPiece::King => Piece::BlackKing,
                         PromotionPiece::Knight => Piece::BlackKnight,
                         PromotionPiece::Queen => Piece::BlackQueen,
                         PromotionPiece::Rook => Piece::BlackRook,
                     },
                     Color::White => match piece {
                         PromotionPiece::Bishop => Piece::WhiteBishop,
+                        PromotionPiece::King => Piece::WhiteKing,
                         PromotionPiece::Knight => Piece::WhiteKnight,
                         PromotionPiece::Queen => Piece::WhiteQueen,
                         PromotionPiece::Rook => Piece::WhiteRook,
@@ -135,66 +290,283 @@ impl Hexchess {
             }
         );
 
-        // clear captured en passant
-        if Some(san.to) == self.ep {
-            let captured = match piece {
-                Piece::BlackPawn => step(san.to, 0),
-                Piece::WhitePawn => step(san.to, 6),
-                _ => None,
-            };
+        // track promoted pieces as they move, and forget captured ones
+        if self.variant.has_drops() {
+            let promoted = san.promotion.is_some() || self.promoted.contains(san.from);
 
-            match captured {
-                Some(position) => self.board[position as usize] = None,
-                None => {},
+            self.promoted.remove(san.from);
+            self.promoted.remove(san.to);
+
+            if promoted {
+                self.promoted.insert(san.to);
+            }
+        }
+
+        let is_pawn = piece == Piece::BlackPawn || piece == Piece::WhitePawn;
+
+        // clear captured en passant, the pawn that passed over the target
+        if is_pawn && Some(san.to) == self.ep {
+            let opponent = match color {
+                Color::Black => Color::White,
+                Color::White => Color::Black,
             };
+
+            if let Some(position) = geometry.step(san.to, geometry.pawn_advance(opponent)) {
+                self.board[position as usize] = None;
+            }
         }
 
         // set en passsant
-        self.ep = match piece {
-            Piece::BlackPawn => match (san.from, san.to) {
-                (h!("c7"), h!("c5")) => Some(h!("c6")),
-                (h!("d7"), h!("d5")) => Some(h!("d6")),
-                (h!("e7"), h!("e5")) => Some(h!("e6")),
-                (h!("f7"), h!("f5")) => Some(h!("f6")),
-                (h!("g7"), h!("g5")) => Some(h!("g6")),
-                (h!("h7"), h!("h5")) => Some(h!("h6")),
-                (h!("i7"), h!("i5")) => Some(h!("i6")),
-                (h!("k7"), h!("k5")) => Some(h!("k6")),
-                _ => None,
+        let forward = geometry.pawn_advance(color);
+
+        self.ep = match is_pawn && geometry.sets_en_passant(san.from, color) {
+            true => geometry.step(san.from, forward).filter(|passed| geometry.step(*passed, forward) == Some(san.to)),
+            false => None,
+        };
+
+        Ok(self)
+    }
+
+    /// drop a piece from the current turn's hand, regardless of legality
+    fn try_apply_drop_unsafe(&mut self, piece: Piece, to: u8) -> Result<&Self, String> {
+        let i = drop_index(piece).unwrap();
+        let color = self.turn;
+
+        if !self.geometry().contains(to) {
+            return Err(format!("invalid position index: {}", to));
+        }
+
+        if self.board[to as usize].is_some() {
+            return Err(format!("cannot drop onto occupied position: {}", to));
+        }
+
+        if self.pockets[side(color)][i] == 0 {
+            return Err(format!("no {} in hand: {}", color_name(color), piece));
+        }
+
+        self.pockets[side(color)][i] -= 1;
+
+        self.board[to as usize] = Some(match color {
+            Color::Black => swap_color(&piece),
+            Color::White => piece,
+        });
+
+        self.halfmove = match piece {
+            Piece::WhitePawn => 0,
+            _ => self.halfmove + 1,
+        };
+
+        if color == Color::Black {
+            self.fullmove += 1;
+        }
+
+        self.turn = match color {
+            Color::Black => Color::White,
+            Color::White => Color::Black,
+        };
+
+        self.ep = None;
+
+        Ok(self)
+    }
+
+    /// count the number of times each position is attacked by a color
+    pub fn attack_map(&self, color: Color) -> [u8; 91] {
+        let mut result: [u8; 91] = [0; 91];
+
+        for n in self.get_color(color) {
+            for to in self.attacks_from(n) {
+                result[to as usize] += 1;
+            }
+        }
+
+        result
+    }
+
+    /// get positions of pieces of a color attacking or defending a position
+    pub fn attackers_of(&self, position: u8, color: Color) -> Vec<u8> {
+        self.get_color(color)
+            .into_iter()
+            .filter(|n| self.attacks_from(*n).contains(&position))
+            .collect()
+    }
+
+    /// get the winner by the variant's own goal, a king reaching the hill, enough checks given, or a king captured
+    pub fn variant_winner(&self) -> Option<Color> {
+        let hill = self.variant.hill();
+
+        [Color::White, Color::Black].into_iter().find(|color| {
+            let opponent = match color {
+                Color::Black => Color::White,
+                Color::White => Color::Black,
+            };
+
+            self.find_king(*color).is_some_and(|king| hill.contains(king)) ||
+            self.variant.check_limit().is_some_and(|limit| self.checks[side(*color)] >= limit) ||
+            (self.variant.has_king_capture() && self.find_king(opponent).is_none())
+        })
+    }
+
+    /// get positions attacked by a piece, including empty and friendly positions
+    pub fn attacks_from(&self, from: u8) -> Vec<u8> {
+        let piece = match self.board[from as usize] {
+            Some(piece) => piece,
+            None => return vec![],
+        };
+
+        match piece {
+            Piece::BlackKing | Piece::WhiteKing => {
+                king_attacks(self.geometry(), from)
+            },
+            Piece::BlackKnight | Piece::WhiteKnight => {
+                knight_attacks(self.geometry(), from)
+            },
+            Piece::BlackPawn | Piece::WhitePawn => {
+                pawn_attacks(self.geometry(), from, &get_color(&piece))
+            },
+            Piece::BlackBishop | Piece::WhiteBishop => {
+                straight_line_attacks(self, &from, &Direction::DIAGONAL)
             },
-            Piece::WhitePawn => match (san.from, san.to) {
-                (h!("c2"), h!("c4")) => Some(h!("c3")),
-                (h!("d3"), h!("d5")) => Some(h!("d4")),
-                (h!("e4"), h!("e6")) => Some(h!("e5")),
-                (h!("f5"), h!("f7")) => Some(h!("f6")),
-                (h!("g4"), h!("g6")) => Some(h!("g5")),
-                (h!("h3"), h!("h5")) => Some(h!("h4")),
-                (h!("i2"), h!("i4")) => Some(h!("i3")),
-                (h!("k1"), h!("k3")) => Some(h!("k2")),
-                _ => None,
+            Piece::BlackRook | Piece::WhiteRook => {
+                straight_line_attacks(self, &from, &Direction::ORTHOGONAL)
+            },
+            Piece::BlackQueen | Piece::WhiteQueen => {
+                straight_line_attacks(self, &from, &Direction::ALL)
             },
-            _ => None,
+        }
+    }
+
+    /// get positions of pieces giving check to the current turn, kings that aren't royal are never in check
+    pub fn checkers(&self) -> Vec<u8> {
+        if !self.variant.has_royal_king() {
+            return vec![];
+        }
+
+        let king = match self.find_king(self.turn) {
+            Some(king) => king,
+            None => return vec![],
+        };
+
+        let opposite_turn = match self.turn {
+            Color::Black => Color::White,
+            Color::White => Color::Black,
         };
 
-        self
+        self.get_color(opposite_turn)
+            .into_iter()
+            .filter(|n| self.moves_from_unsafe(*n).iter().any(|san| san.to == king))
+            .collect()
+    }
+
+    /// remove all pieces from the board
+    pub fn clear(&mut self) {
+        self.board = Board::new(self.geometry().cells);
+        self.ep = None;
+        self.promoted = CellSet::default();
     }
 
     /// get legal moves for current turn
     pub fn current_moves(&self) -> Vec<San> {
         let mut result: Vec<San> = vec![];
+        let captures_only = self.variant.has_forced_captures() && self.has_captures(self.turn);
 
         for n in self.get_color(self.turn) {
-            result.extend(self.moves_from(n));
+            result.extend(self.legal_moves_from(n, captures_only));
+        }
+
+        result.extend(self.drop_moves());
+
+        result
+    }
+
+    /// get positions of current turn pieces that would give check by moving off a line
+    pub fn discovered_check_candidates(&self) -> Vec<u8> {
+        let opposite_turn = match self.turn {
+            Color::Black => Color::White,
+            Color::White => Color::Black,
+        };
+
+        match self.find_king(opposite_turn) {
+            Some(king) => self.x_rays(king, self.turn)
+                .iter()
+                .map(|pin| pin.pinned)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// get legal drops of pieces in the current turn's hand
+    pub fn drop_moves(&self) -> Vec<San> {
+        let mut result: Vec<San> = vec![];
+
+        if !self.variant.has_drops() {
+            return result;
+        }
+
+        let geometry = self.geometry();
+
+        for (i, piece) in DROP_PIECES.iter().enumerate() {
+            if self.pockets[side(self.turn)][i] == 0 {
+                continue;
+            }
+
+            for to in geometry.cells.iter() {
+                // pawns may not be dropped where they would promote, or onto the opponent's promotion cells
+                if self.board[to as usize].is_some() || (*piece == Piece::WhitePawn && (
+                    geometry.is_promotion(to, Color::White) ||
+                    geometry.is_promotion(to, Color::Black)
+                )) {
+                    continue;
+                }
+
+                let san = San::new_drop(*piece, to).unwrap();
+
+                if !self.is_self_check(&san, self.turn) {
+                    result.push(san);
+                }
+            }
         }
 
         result
     }
 
+    /// swap piece colors and rotate the board half a turn, an equivalent position with roles reversed
+    pub fn flip_colors(&self) -> Self {
+        let geometry = self.geometry();
+        let rotate = |position: u8| geometry.rotate(position);
+        let mut board = Board::new(geometry.cells);
+
+        for position in geometry.cells.iter() {
+            board[rotate(position) as usize] = self.board[position as usize].map(|piece| swap_color(&piece));
+        }
+
+        Self {
+            board,
+            castling: self.transform_castling(rotate),
+            checks: [self.checks[1], self.checks[0]],
+            ep: self.ep.map(rotate),
+            fullmove: self.fullmove,
+            halfmove: self.halfmove,
+            pockets: [self.pockets[1], self.pockets[0]],
+            promoted: self.promoted.iter().map(rotate).collect(),
+            turn: match self.turn {
+                Color::Black => Color::White,
+                Color::White => Color::Black,
+            },
+            variant: self.variant,
+        }
+    }
+
+    /// get the board geometry of the position's variant
+    pub fn geometry(&self) -> &'static BoardGeometry {
+        self.variant.geometry()
+    }
+
     /// get piece at position
     pub fn get(&self, position: &str) -> Option<Piece> {
-        match index(position) {
-            Ok(index) => self.board[index as usize],
-            Err(_) => None,
+        match self.geometry().position_index(position) {
+            Some(index) => self.board[index as usize],
+            None => None,
         }
     }
 
@@ -202,10 +574,10 @@ impl Hexchess {
     pub fn get_color(&self, color: Color) -> Vec<u8> {
         let mut result: Vec<u8> = vec![];
 
-        for (index, piece) in self.board.iter().enumerate() {
+        for (index, piece) in self.board.iter() {
             match piece {
-                Some(piece) => match get_color(piece) == color {
-                    true => result.push(index as u8),
+                Some(piece) => match get_color(&piece) == color {
+                    true => result.push(index),
                     false => continue,
                 },
                 None => continue,
@@ -215,6 +587,24 @@ impl Hexchess {
         result
     }
 
+    /// reflect the board across the f-file
+    pub fn mirror(&self) -> Self {
+        let geometry = self.geometry();
+        let mut board = Board::new(geometry.cells);
+
+        for position in geometry.cells.iter() {
+            board[geometry.mirror(position) as usize] = self.board[position as usize];
+        }
+
+        Self {
+            board,
+            castling: self.transform_castling(|position| geometry.mirror(position)),
+            ep: self.ep.map(|ep| geometry.mirror(ep)),
+            promoted: self.promoted.iter().map(|position| geometry.mirror(position)).collect(),
+            ..*self
+        }
+    }
+
     /// get legal moves a position
     pub fn moves_from(&self, from: u8) -> Vec<San> {
         let piece = match self.board[from as usize] {
@@ -224,20 +614,8 @@ impl Hexchess {
 
         let color = get_color(&piece);
 
-        self.moves_from_unsafe(from)
-            .into_iter()
-            .filter(|san| {
-                // prevent self check
-                let mut clone = self.clone();
-
-                clone.apply_move_unsafe(san);
-                
-                match clone.find_king(color) {
-                    Some(king) => !clone.is_threatened(king),
-                    None => true,
-                }
-            })
-            .collect()
+        // captures are compulsory, so other moves are only legal when no piece can capture
+        self.legal_moves_from(from, self.variant.has_forced_captures() && self.has_captures(color))
     }
 
     /// get moves from a position, regardless of turn or legality
@@ -262,13 +640,13 @@ impl Hexchess {
                 pawn_moves_unsafe(&self, from, &color)
             },
             Piece::BlackBishop | Piece::WhiteBishop => {
-                straight_line_moves_unsafe(&self, &from, &color, &[1, 3, 5, 7, 9, 11])
+                straight_line_moves_unsafe(&self, &from, &color, &Direction::DIAGONAL)
             },
             Piece::BlackRook | Piece::WhiteRook => {
-                straight_line_moves_unsafe(&self, &from, &color, &[0, 2, 4, 6, 8, 10])
+                straight_line_moves_unsafe(&self, &from, &color, &Direction::ORTHOGONAL)
             },
             Piece::BlackQueen | Piece::WhiteQueen => {
-                straight_line_moves_unsafe(&self, &from, &color, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11])
+                straight_line_moves_unsafe(&self, &from, &color, &Direction::ALL)
             }
         });
         
@@ -277,12 +655,22 @@ impl Hexchess {
 
     /// create a new hexchess instance
     pub fn new() -> Self {
+        Self::new_variant(Variant::Glinski)
+    }
+
+    /// create an empty hexchess instance of a variant
+    pub fn new_variant(variant: Variant) -> Self {
         Self {
-            board: [None; 91],
+            board: Board::new(variant.geometry().cells),
+            castling: [false; 4],
+            checks: [0; 2],
             ep: None,
             fullmove: 1,
             halfmove: 0,
+            pockets: [[0; 5]; 2],
+            promoted: CellSet::default(),
             turn: Color::White,
+            variant,
         }
     }
 
@@ -293,9 +681,9 @@ impl Hexchess {
             Color::White => Piece::WhiteKing,
         };
 
-        for (index, piece) in self.board.iter().enumerate() {
-            if piece == &Some(king) {
-                return Some(index as u8);
+        for (index, piece) in self.board.iter() {
+            if piece == Some(king) {
+                return Some(index);
             }
         }
 
@@ -304,11 +692,20 @@ impl Hexchess {
 
     /// initialize a hexchess instance to the starting position
     pub fn init() -> Self {
-        Self::parse(INITIAL_POSITION).unwrap()
+        Self::init_variant(Variant::Glinski)
+    }
+
+    /// initialize a hexchess instance to the starting position of a variant
+    pub fn init_variant(variant: Variant) -> Self {
+        Self::parse_variant(variant.geometry().initial_position, variant).unwrap()
     }
 
     /// test if the board is in check
     pub fn is_check(&self) -> bool {
+        if !self.variant.has_royal_king() {
+            return false;
+        }
+
         let king = match self.find_king(self.turn) {
             Some(king) => king,
             None => return false
@@ -337,9 +734,13 @@ impl Hexchess {
 
     /// test if move is legal
     pub fn is_legal(&self, san: &San) -> bool {
-        let piece = match self.board[san.from as usize] {
-            Some(piece) => piece,
-            None => return false,
+        if san.dropped().is_some() {
+            return self.drop_moves().contains(san);
+        }
+
+        let piece = match self.board.get(san.from as usize) {
+            Some(Some(piece)) => *piece,
+            _ => return false,
         };
         
         if get_color(&piece) != self.turn {
@@ -386,13 +787,26 @@ impl Hexchess {
 
     /// create hexchess instance from fen
     pub fn parse(source: &str) -> Result<Self, String> {
-        let mut parts = source.split_whitespace();
+        Self::parse_variant(source, Variant::Glinski)
+    }
 
-        let board = match parts.next() {
-            Some(part) => match parse_board(&part.to_string()) {
-                Ok(result) => result,
-                Err(failure) => return Err(failure),
-            }
+    /// create hexchess instance from fen of a variant's board
+    pub fn parse_variant(source: &str, variant: Variant) -> Result<Self, String> {
+        let geometry = variant.geometry();
+        let mut parts = source.split_whitespace().peekable();
+
+        let (board, promoted, pockets) = match parts.next() {
+            Some(part) => {
+                // pieces in hand follow the board in brackets, such as `[Nn]`
+                let (part, pockets) = match (variant.has_drops(), part.split_once('[')) {
+                    (true, Some((part, pockets))) => (part, parse_pockets(pockets)?),
+                    _ => (part, [[0; 5]; 2]),
+                };
+
+                let (board, promoted) = parse_board(&part.to_string(), variant)?;
+
+                (board, promoted, pockets)
+            },
             _ => return Err("board not found".to_string()),
         };
 
@@ -405,20 +819,37 @@ impl Hexchess {
             None => Color::White,
         };
 
+        let castling = match geometry.has_castling() {
+            true => match parts.next() {
+                Some(part) => parse_castling(part, geometry)?,
+                None => [false; 4],
+            },
+            false => [false; 4],
+        };
+
         let ep = match parts.next() {
             Some(part) => match part {
                 "-" => None,
-                _ => match index(&part) {
-                    Ok(result) => match is_legal_en_passant(&result) {
+                _ => match geometry.position_index(part) {
+                    Some(result) => match is_en_passant_position(geometry, result) {
                         true => Some(result),
                         false => return Err(format!("illegal en passant position: {}", part)),
                     },
-                    Err(_) => return Err(format!("invalid en passant position: {}", part)),
+                    None => return Err(format!("invalid en passant position: {}", part)),
                 },
             },
             None => None,
         };
 
+        // checks given are only written by variants with a check limit, and may be omitted
+        let checks = match variant.check_limit() {
+            Some(limit) => match parts.next_if(|part| part.contains('+')) {
+                Some(part) => parse_checks(part, limit)?,
+                None => [0; 2],
+            },
+            None => [0; 2],
+        };
+
         let halfmove = match parts.next() {
             Some(part) => match part.parse::<u8>() {
               Ok(result) => result,
@@ -440,1071 +871,2758 @@ impl Hexchess {
 
         Ok(Self {
             board,
+            castling,
+            checks,
             ep,
             fullmove,
             halfmove,
+            pockets,
+            promoted,
             turn,
+            variant,
         })
     }
 
-    /// format as fen string
-    pub fn to_string(&self) -> String {
-        format!(
-            "{} {} {} {} {}",
-            stringify_board(&self.board),
-            match self.turn {
-                Color::Black => 'b',
-                Color::White => 'w',
-            },
-            match self.ep {
-                Some(ep) => to_position(&ep),
-                None => "-",
-            },
-            self.halfmove,
-            self.fullmove,
-        )
+    /// parse fen, rejecting positions that could not have been reached with every reason joined by `; `
+    pub fn parse_strict(source: &str) -> Result<Self, String> {
+        Self::parse_strict_variant(source, Variant::Glinski)
     }
-}
 
-/// parse the board segment of fen
-fn parse_board(source: &String) -> Result<[Option<Piece>; 91], String> {
-    let mut arr: [Option<Piece>; 91] = [None; 91];
-    let mut black = false;
-    let mut white = false;
-    let mut fen_index: u8 = 0;
+    /// parse fen of a variant's board, rejecting positions that could not have been reached with every reason joined by `; `
+    pub fn parse_strict_variant(source: &str, variant: Variant) -> Result<Self, String> {
+        let hexchess = Self::parse_variant(source, variant)?;
 
-    for (index, current) in source.chars().enumerate() {
-        match current {
-            '/' => continue,
-            '0' => continue,
-            '1' => match source.chars().nth(index as usize + 1) {
-                Some('0') | Some('1') => fen_index += 10,
-                _ => fen_index += 1,
-            },
-            '2' => fen_index += 2,
-            '3' => fen_index += 3,
-            '4' => fen_index += 4,
-            '5' => fen_index += 5,
-            '6' => fen_index += 6,
-            '7' => fen_index += 7,
-            '8' => fen_index += 8,
-            '9' => fen_index += 9,
-            'b' | 'B' | 'n' | 'N' | 'p' | 'P' | 'Q' | 'q' | 'r' | 'R' => {
-                // // it's safe to unwrap current because our match already checks for it
-                arr[fen_index as usize] = Some(to_piece(current).unwrap());
+        match hexchess.validate() {
+            Ok(_) => Ok(hexchess),
+            Err(errors) => Err(errors.join("; ")),
+        }
+    }
 
-                fen_index += 1;
+    /// get pieces pinned to the king of a color
+    pub fn pinned(&self, color: Color) -> Vec<Pin> {
+        match self.find_king(color) {
+            Some(king) => self.x_rays(king, color),
+            None => vec![],
+        }
+    }
+
+    /// get a copy of the position with only what a color can see, hiding the
+    /// opponent's castling rights and pieces on cells it can't see
+    pub fn redacted_view(&self, color: Color) -> Self {
+        let visible = self.visible_cells(color);
+        let mut result = *self;
+
+        for position in self.geometry().cells.iter() {
+            if !visible[position as usize] {
+                result.board[position as usize] = None;
             }
-            'k' => {
-                if black {
-                    return Err("multiple black kings".to_string());
-                }
+        }
 
-                arr[fen_index as usize] = Some(Piece::BlackKing);
-                black = true;
-                fen_index += 1;
+        for (i, castling) in self.geometry().castling.iter().enumerate() {
+            if castling.is_some_and(|castling| castling.color != color) {
+                result.castling[i] = false;
             }
-            'K' => {
-                if white {
-                    return Err("multiple white kings".to_string());
-                }
-
-                arr[fen_index as usize] = Some(Piece::WhiteKing);
-                white = true;
-                fen_index += 1;
-            },
-            _ => return Err(format!("invalid character at index {}: {}", index, current)),
         }
-    }
 
-    if fen_index != 91 {
-        return Err("board overflow".to_string());
-    }
+        result.ep = self.ep.filter(|ep| visible[*ep as usize]);
+        result.promoted = self.promoted.iter().filter(|position| visible[*position as usize]).collect();
 
-    Ok(arr)
-}
+        result
+    }
 
-/// format the board section of a fen
-fn stringify_board(board: &[Option<Piece>; 91]) -> String {
-    let mut blank: u8 = 0;
-    let mut index: u8 = 0;
-    let mut result = String::new();
+    /// remove the piece at a position
+    pub fn remove(&mut self, position: &str) -> Result<(), String> {
+        self.set(position, None)
+    }
 
-    for val in board.iter() {
-        match val {
-            None => {
-                blank += 1;
-            },
-            Some(piece) => {
-                if blank > 0 {
-                    result.push_str(&blank.to_string());
-                    blank = 0;
-                }
+    /// place a piece at a position, or clear it with `None`
+    pub fn set(&mut self, position: &str, piece: Option<Piece>) -> Result<(), String> {
+        match self.geometry().position_index(position) {
+            Some(index) => {
+                self.board[index as usize] = piece;
+                self.promoted.remove(index);
 
-                result.push(match piece {
-                    Piece::BlackBishop => 'b',
-                    Piece::BlackKing => 'k',
-                    Piece::BlackKnight => 'n',
-                    Piece::BlackPawn => 'p',
-                    Piece::BlackQueen => 'q',
-                    Piece::BlackRook => 'r',
-                    Piece::WhiteBishop => 'B',
-                    Piece::WhiteKing => 'K',
-                    Piece::WhiteKnight => 'N',
-                    Piece::WhitePawn => 'P',
-                    Piece::WhiteQueen => 'Q',
-                    Piece::WhiteRook => 'R',
-                });
+                Ok(())
             },
-        };
-
-        match index {
-            0 | 3 | 8 | 15 | 24 | 35 | 46 | 57 | 68 | 79 => {
-                if blank > 0 {
-                    result.push_str(&blank.to_string());
-                }
+            None => Err(format!("invalid position: {}", position)),
+        }
+    }
 
-                result.push('/');
-                blank = 0;
+    /// set the en passant position, or clear it with `None`
+    pub fn set_ep(&mut self, position: Option<&str>) -> Result<(), String> {
+        self.ep = match position {
+            Some(position) => match self.geometry().position_index(position) {
+                Some(result) => match is_en_passant_position(self.geometry(), result) {
+                    true => Some(result),
+                    false => return Err(format!("illegal en passant position: {}", position)),
+                },
+                None => return Err(format!("invalid en passant position: {}", position)),
             },
-            _ => {}
+            None => None,
         };
 
-        index += 1;
+        Ok(())
     }
 
-    if blank > 0 {
-        result.push_str(&blank.to_string());
+    /// set the color to move
+    pub fn set_turn(&mut self, color: Color) {
+        self.turn = color;
     }
 
-    result
-}
+    /// find every reason the position could not have been reached
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors: Vec<String> = vec![];
+        let geometry = self.geometry();
+        let initial = Self::init_variant(self.variant);
 
-/// convert character to piece
-fn to_piece(source: char) -> Result<Piece, &'static str> {
-    match source {
-        'p' => Ok(Piece::BlackPawn),
-        'n' => Ok(Piece::BlackKnight),
-        'b' => Ok(Piece::BlackBishop),
-        'r' => Ok(Piece::BlackRook),
-        'q' => Ok(Piece::BlackQueen),
-        'k' => Ok(Piece::BlackKing),
-        'P' => Ok(Piece::WhitePawn),
-        'N' => Ok(Piece::WhiteKnight),
-        'B' => Ok(Piece::WhiteBishop),
-        'R' => Ok(Piece::WhiteRook),
-        'Q' => Ok(Piece::WhiteQueen),
-        'K' => Ok(Piece::WhiteKing),
-        _ => Err("invalid_piece_character")
-    }
-}
+        if self.board.cells() != geometry.cells {
+            return Err(vec![format!("board does not fit the variant: {} cells", self.board.len())]);
+        }
 
-#[cfg(test)]
-mod tests {
-    use crate::{h, s};
-    use super::*;
+        for color in [Color::White, Color::Black] {
+            let name = color_name(color);
 
-    mod apply {
-        use super::*;
+            let (king, pawn, knight, bishop, rook, queen) = match color {
+                Color::White => (Piece::WhiteKing, Piece::WhitePawn, Piece::WhiteKnight, Piece::WhiteBishop, Piece::WhiteRook, Piece::WhiteQueen),
+                Color::Black => (Piece::BlackKing, Piece::BlackPawn, Piece::BlackKnight, Piece::BlackBishop, Piece::BlackRook, Piece::BlackQueen),
+            };
 
-        #[test]
-        fn test_applying_a_sequence_of_moves() {
-            let mut hexchess = Hexchess::init();
-            let _ = hexchess.apply("g4g6 f7g6 f5f7 g6f6");
+            let count = |piece: Piece| self.board.pieces().iter().filter(|p| **p == Some(piece)).count();
+            let initial_count = |piece: Piece| initial.board.pieces().iter().filter(|p| **p == Some(piece)).count();
 
-            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/pppp1pppp/5p5/11/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 3");
-        }
+            // kings, which may be captured when they aren't royal
+            match count(king) {
+                0 if !self.variant.has_royal_king() => {},
+                0 => errors.push(format!("missing {} king", name)),
+                1 => {},
+                _ if self.variant.has_king_promotion() => {},
+                _ => errors.push(format!("multiple {} kings", name)),
+            }
 
-        #[test]
-        fn test_apply_with_invalid_san() {
-            let mut hexchess = Hexchess::init();
-            let result = hexchess.apply("whoops");
+            // pawn placement
+            let reachable = pawn_reachable(&initial, color);
 
-            assert_eq!(result, Err("invalid san at index 0: whoops".to_string()));
+            for position in 0u8..91u8 {
+                if self.board[position as usize] != Some(pawn) {
+                    continue;
+                }
+
+                if geometry.is_promotion(position, color) {
+                    errors.push(format!("{} pawn on promotion position: {}", name, geometry.position_name(position).unwrap_or_default()));
+                } else if !reachable[position as usize] && !self.variant.has_drops() {
+                    errors.push(format!("{} pawn behind starting position: {}", name, geometry.position_name(position).unwrap_or_default()));
+                }
+            }
+
+            // piece counts
+            let pawns = count(pawn);
+            let max_pawns = initial_count(pawn);
+
+            let promoted = count(knight).saturating_sub(initial_count(knight))
+                + count(bishop).saturating_sub(initial_count(bishop))
+                + count(rook).saturating_sub(initial_count(rook))
+                + count(queen).saturating_sub(initial_count(queen))
+                + match self.variant.has_king_promotion() {
+                    true => count(king).saturating_sub(initial_count(king)),
+                    false => 0,
+                };
+
+            if self.variant.has_drops() {
+                continue; // <- pieces change color when dropped, so they are counted together below
+            }
+
+            if pawns > max_pawns {
+                errors.push(format!("too many {} pawns: {}", name, pawns));
+            } else if pawns + promoted > max_pawns {
+                errors.push(format!("too many {} promoted pieces: {}", name, promoted));
+            }
         }
 
-        #[test]
-        fn test_apply_with_illegal_move() {
-            let mut hexchess = Hexchess::init();
-            let result = hexchess.apply("g4g5 a6a5");
+        // pieces on the board and in hand
+        if self.variant.has_drops() {
+            let total = self.board.pieces().iter().flatten().filter(|piece| drop_index(**piece).is_some()).count()
+                + self.pockets.iter().flatten().map(|count| *count as usize).sum::<usize>();
 
-            assert_eq!(result, Err("illegal move at index 1: a6a5".to_string()));
+            let max = initial.board.pieces().iter().flatten().filter(|piece| drop_index(**piece).is_some()).count();
+
+            if total > max {
+                errors.push(format!("too many pieces on the board and in hand: {}", total));
+            }
         }
-    }
 
-    mod apply_move {
-        use super::*;
+        // castling rights
+        for (i, castling) in geometry.castling.iter().enumerate() {
+            let castling = match (self.castling[i], castling) {
+                (true, Some(castling)) => castling,
+                _ => continue,
+            };
 
-        #[test]
-        fn sets_to_and_from_positions() {
-            let mut hexchess = Hexchess::init();
-            let _ = hexchess.apply_move(&s!("g4g5"));
-            let _ = hexchess.apply_move(&s!("e7e6"));
-            assert_eq!(hexchess.board[h!("g5")], Some(Piece::WhitePawn));
-            assert_eq!(hexchess.board[h!("g4")], None);
-            assert_eq!(hexchess.board[h!("e6")], Some(Piece::BlackPawn));
-            assert_eq!(hexchess.board[h!("e7")], None);
+            let (king, rook) = match castling.color {
+                Color::Black => (Piece::BlackKing, Piece::BlackRook),
+                Color::White => (Piece::WhiteKing, Piece::WhiteRook),
+            };
+
+            if self.board[castling.king as usize] != Some(king) || self.board[castling.rook as usize] != Some(rook) {
+                errors.push(format!("castling right without king and rook in place: {}", CASTLING_RIGHTS[i]));
+            }
         }
 
-        #[test]
-        fn clears_en_pasant_capture_white() {
-            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/ppppp1ppp/5P5/6p4/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w g6 0 2").unwrap();
-            let _ = hexchess.apply_move(&s!("f6g6"));
+        // side not to move in check
+        let opponent = match self.turn {
+            Color::Black => Color::White,
+            Color::White => Color::Black,
+        };
 
-            assert_eq!(hexchess.get("g5"), None);
+        if let Some(king) = self.find_king(opponent).filter(|_| self.variant.has_royal_king()) {
+            if !self.attackers_of(king, self.turn).is_empty() {
+                errors.push(format!("{} king is in check with {} to move", color_name(opponent), color_name(self.turn)));
+            }
         }
 
-        #[test]
-        fn clears_en_passant_capture_black() {
-            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/pppp1pppp/5pP4/4PP5/11/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 2").unwrap();
-            let _ = hexchess.apply_move(&s!("f6g5"));
+        // en passant
+        if let Some(ep) = self.ep {
+            let position = geometry.position_name(ep).unwrap_or_default();
 
-            assert_eq!(hexchess.get("g6"), None);
+            let (consistent, forward, pawn) = match self.turn {
+                Color::White => (geometry.is_en_passant(ep, Color::Black), geometry.pawn_advance(Color::Black), Piece::BlackPawn),
+                Color::Black => (geometry.is_en_passant(ep, Color::White), geometry.pawn_advance(Color::White), Piece::WhitePawn),
+            };
+
+            if !consistent {
+                errors.push(format!("en passant position inconsistent with turn: {}", position));
+            } else {
+                if self.board[ep as usize].is_some() {
+                    errors.push(format!("en passant position is occupied: {}", position));
+                }
+
+                if geometry.step(ep, forward).and_then(|n| self.board[n as usize]) != Some(pawn) {
+                    errors.push(format!("no pawn beyond en passant position: {}", position));
+                }
+
+                if geometry.step(ep, forward.opposite()).and_then(|n| self.board[n as usize]).is_some() {
+                    errors.push(format!("en passant starting position is occupied: {}", position));
+                }
+            }
         }
 
-        #[test]
-        fn only_pawns_capture_en_passant() {
-            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/ppppp1ppp/11/5Pp4/4P1PB3/3P1B1P3/2P5P2/1PRNQBKNRP1 w g6 0 2").unwrap();
-            let _ = hexchess.apply_move(&s!("h4g6")); // <- bishop to en passant
-            
-            assert_eq!(hexchess.get("g5"), Some(Piece::BlackPawn));
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
         }
+    }
 
-        #[test]
-        fn alternate_color_back_and_forth() {
-            let mut hexchess = Hexchess::init();
+    /// get cells visible to a color, those it's pieces occupy or could move to
+    pub fn visible_cells(&self, color: Color) -> [bool; 91] {
+        let mut result = [false; 91];
 
-            assert_eq!(hexchess.turn, Color::White);
-            let _ = hexchess.apply_move(&s!("g4g5"));
-            assert_eq!(hexchess.turn, Color::Black);
-            let _ = hexchess.apply_move(&s!("e7e6"));
-            assert_eq!(hexchess.turn, Color::White);
-            let _ = hexchess.apply_move(&s!("f5f6"));
-            assert_eq!(hexchess.turn, Color::Black);
+        for from in self.get_color(color) {
+            result[from as usize] = true;
+
+            for san in self.moves_from_unsafe(from) {
+                result[san.to as usize] = true;
+            }
         }
 
-        #[test]
-        fn sets_and_unsets_en_passant() {
-            let mut hexchess = Hexchess::init();
+        result
+    }
 
-            let _ = hexchess.apply_move(&s!("g4g6"));
-            assert_eq!(hexchess.ep, Some(h!("g5")));
+    /// test if a move would leave the king of a color threatened
+    fn is_self_check(&self, san: &San, color: Color) -> bool {
+        if !self.variant.has_royal_king() {
+            return false;
+        }
 
-            let _ = hexchess.apply_move(&s!("e7e5"));
-            assert_eq!(hexchess.ep, Some(h!("e6")));
+        let mut clone = *self;
 
-            let _ = hexchess.apply_move(&s!("b1b2"));
-            assert_eq!(hexchess.ep, None);
+        clone.apply_move_unsafe(san);
+
+        match clone.find_king(color) {
+            Some(king) => clone.is_threatened(king),
+            None => false,
         }
+    }
 
-        #[test]
-        fn sets_halfmove_and_fullmove() {
-            let mut hexchess = Hexchess::init();
+    /// test if any piece of a color can capture, ignoring check
+    fn has_captures(&self, color: Color) -> bool {
+        self.get_color(color)
+            .into_iter()
+            .any(|n| self.moves_from_unsafe(n).iter().any(|san| self.is_capture(san)))
+    }
 
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.fullmove, 1);
+    /// get legal moves from a position, optionally only those that capture
+    fn legal_moves_from(&self, from: u8, captures_only: bool) -> Vec<San> {
+        let color = match self.board[from as usize] {
+            Some(piece) => get_color(&piece),
+            None => return vec![],
+        };
 
-            let _ = hexchess.apply_move(&s!("e4e5"));
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.fullmove, 1);
+        let moves = self.moves_from_unsafe(from)
+            .into_iter()
+            .chain(castling_moves(self, from, &color))
+            .filter(|san| !self.is_self_check(san, color));
 
-            let _ = hexchess.apply_move(&s!("f7f6"));
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.fullmove, 2);
+        match captures_only {
+            true => moves.filter(|san| self.is_capture(san)).collect(),
+            false => moves.collect(),
+        }
+    }
 
-            let _ = hexchess.apply_move(&s!("f3c6"));
-            assert_eq!(hexchess.halfmove, 1);
-            assert_eq!(hexchess.fullmove, 2);
+    /// test if a move captures a piece, including en passant
+    fn is_capture(&self, san: &San) -> bool {
+        let piece = match self.board.get(san.from as usize) {
+            Some(Some(piece)) => *piece,
+            _ => return false,
+        };
 
-            let _ = hexchess.apply_move(&s!("i8h8"));
-            assert_eq!(hexchess.halfmove, 2);
-            assert_eq!(hexchess.fullmove, 3);
+        self.board[san.to as usize].is_some() || (
+            Some(san.to) == self.ep &&
+            (piece == Piece::BlackPawn || piece == Piece::WhitePawn)
+        )
+    }
 
-            let _ = hexchess.apply_move(&s!("c6e10"));
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.fullmove, 3);
-        }
+    /// map castling rights through a transformation of positions, keeping
+    /// only those that land on the starting positions of another castle
+    fn transform_castling(&self, f: impl Fn(u8) -> u8) -> [bool; 4] {
+        let castling = self.geometry().castling;
+        let mut result = [false; 4];
 
-        // promote white pieces
-        #[test]
-        fn white_and_black_promotions() {
-            let mut hexchess = Hexchess::parse("1/3/1P1P1/7/1P5P1/11/11/11/11/2p1p1p1p2/11 w - 0 1").unwrap();
+        for (i, right) in self.castling.iter().enumerate() {
+            let from = match (right, castling[i]) {
+                (true, Some(from)) => from,
+                _ => continue,
+            };
 
-            let _ = hexchess.apply_move(&s!("c7c8r"));
-            assert_eq!(hexchess.board[h!("c8")], Some(Piece::WhiteRook));
+            let to = castling.iter().position(|to| match to {
+                Some(to) => to.king == f(from.king) && to.rook == f(from.rook),
+                None => false,
+            });
 
-            let _ = hexchess.apply_move(&s!("c2c1r"));
-            assert_eq!(hexchess.board[h!("c1")], Some(Piece::BlackRook));
+            if let Some(j) = to {
+                result[j] = true;
+            }
+        }
 
-            let _ = hexchess.apply_move(&s!("e9e10b"));
-            assert_eq!(hexchess.board[h!("e10")], Some(Piece::WhiteBishop));
+        result
+    }
 
-            let _ = hexchess.apply_move(&s!("e2e1b"));
-            assert_eq!(hexchess.board[h!("e1")], Some(Piece::BlackBishop));
+    /// find lone pieces of a color between a king and a hostile slider
+    fn x_rays(&self, king: u8, color: Color) -> Vec<Pin> {
+        let mut result: Vec<Pin> = vec![];
 
-            let _ = hexchess.apply_move(&s!("g9g10q"));
-            assert_eq!(hexchess.board[h!("g10")], Some(Piece::WhiteQueen));
+        let king_color = match self.board[king as usize] {
+            Some(piece) => get_color(&piece),
+            None => return result,
+        };
 
-            let _ = hexchess.apply_move(&s!("g2g1q"));
-            assert_eq!(hexchess.board[h!("g1")], Some(Piece::BlackQueen));
+        for direction in 0u8..12u8 {
+            let mut position = king;
+            let mut pinned: Option<u8> = None;
 
-            let _ = hexchess.apply_move(&s!("i7i8n"));
-            assert_eq!(hexchess.board[h!("i8")], Some(Piece::WhiteKnight));
+            while let Some(next) = self.geometry().step(position, direction) {
+                position = next;
 
-            let _ = hexchess.apply_move(&s!("i2i1n"));
-            assert_eq!(hexchess.board[h!("i1")], Some(Piece::BlackKnight));
-        }
-    
-        #[test]
-        fn errors_on_illegal_move() {
-            let mut hexchess = Hexchess::init();
+                let piece = match self.board[position as usize] {
+                    Some(piece) => piece,
+                    None => continue,
+                };
 
-            assert_eq!(hexchess.apply_move(&s!("a4a5")).is_err(), true);
+                match pinned {
+                    None => match get_color(&piece) == color {
+                        true => pinned = Some(position),
+                        false => break, // <- nothing stands between king and piece
+                    },
+                    Some(pinned) => {
+                        if get_color(&piece) != king_color && slides_along(&piece, direction) {
+                            result.push(Pin { direction, pinned, pinner: position });
+                        }
+
+                        break;
+                    },
+                };
+            }
         }
 
-        #[test]
-        #[should_panic]
-        fn apply_move_unsafe_panics_on_empty_positions() {
-            let mut hexchess = Hexchess::init();
+        result
+    }
+}
 
-            hexchess.apply_move_unsafe(&s!("a4a5"));
-        }
+impl FromStr for Hexchess {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
     }
+}
 
-    #[test]
-    fn test_clone() {
-        let hexchess = Hexchess::init();
-        let clone = hexchess.clone();
+impl TryFrom<&str> for Hexchess {
+    type Error = String;
 
-        assert_eq!(clone.board, hexchess.board);
-        assert_eq!(clone.ep, hexchess.ep);
-        assert_eq!(clone.turn, hexchess.turn);
-        assert_eq!(clone.halfmove, hexchess.halfmove);  
-        assert_eq!(clone.fullmove, hexchess.fullmove);
+    fn try_from(source: &str) -> Result<Self, Self::Error> {
+        Self::parse(source)
     }
+}
 
-    #[test]
-    fn test_apply_move_unsafe() {
-        let mut hexchess = Hexchess::init();
+impl Index<Cell> for Hexchess {
+    type Output = Option<Piece>;
 
-        hexchess.apply_move_unsafe(&s!("b1b6")); // <- illegal pawn move
+    fn index(&self, cell: Cell) -> &Self::Output {
+        &self.board[cell.index() as usize]
+    }
+}
 
-        assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/1P9/5P5/4P1P4/3P1B1P3/2P2B2P2/2RNQBKNRP1 b - 0 1");
+impl IndexMut<Cell> for Hexchess {
+    fn index_mut(&mut self, cell: Cell) -> &mut Self::Output {
+        &mut self.board[cell.index() as usize]
     }
+}
 
-    #[test]
-    fn test_current_moves() {
-        let hexchess = Hexchess::init();
-        let result = hexchess.current_moves().iter().map(|s| s.to_string()).collect::<Vec<String>>();
+/// Formats as fen, or as a text diagram with the alternate flag, `{:#}`
+/// Boards are matched to a variant by their number of cells, so a board must fit the game's variant
+#[cfg(feature = "serde")]
+impl TryFrom<HexchessFields> for Hexchess {
+    type Error = String;
+
+    fn try_from(fields: HexchessFields) -> Result<Self, Self::Error> {
+        if fields.board.cells() != fields.variant.geometry().cells {
+            return Err(format!("board does not fit the variant: {} cells", fields.board.len()));
+        }
+
+        Ok(Hexchess {
+            board: fields.board,
+            castling: fields.castling,
+            checks: fields.checks,
+            ep: fields.ep,
+            fullmove: fields.fullmove,
+            halfmove: fields.halfmove,
+            pockets: fields.pockets,
+            promoted: fields.promoted,
+            turn: fields.turn,
+            variant: fields.variant,
+        })
+    }
+}
 
-        assert_eq!(result.len(), 51);
-        assert_eq!(result[0], "f5f6");
-        assert_eq!(result[1], "e4e5");
-        assert_eq!(result[2], "e4e6");
-        assert_eq!(result[3], "g4g5");
-        assert_eq!(result[4], "g4g6");
-        assert_eq!(result[5], "d3d4");
-        assert_eq!(result[6], "d3d5");
-        assert_eq!(result[7], "f3h2");
-        assert_eq!(result[8], "f3d2");
-        assert_eq!(result[9], "h3h4");
-        assert_eq!(result[10], "h3h5");
-        assert_eq!(result[11], "c2c3");
-        assert_eq!(result[12], "c2c4");
-        assert_eq!(result[13], "f2g3");
-        assert_eq!(result[14], "f2h4");
-        assert_eq!(result[15], "f2i5");
-        assert_eq!(result[16], "f2k6");
-        assert_eq!(result[17], "f2e3");
-        assert_eq!(result[18], "f2d4");
-        assert_eq!(result[19], "f2c5");
-        assert_eq!(result[20], "f2b6");
-        assert_eq!(result[21], "i2i3");
-        assert_eq!(result[22], "i2i4");
-        assert_eq!(result[23], "b1b2");
-        assert_eq!(result[24], "b1b3");
-        assert_eq!(result[25], "c1d2");
-        assert_eq!(result[26], "c1e3");
-        assert_eq!(result[27], "c1f4");
-        assert_eq!(result[28], "d1f4");
-        assert_eq!(result[29], "d1g2");
-        assert_eq!(result[30], "d1b2");
-        assert_eq!(result[31], "d1c3");
-        assert_eq!(result[32], "e1e2");
-        assert_eq!(result[33], "e1e3");
-        assert_eq!(result[34], "e1d2");
-        assert_eq!(result[35], "e1c3");
-        assert_eq!(result[36], "e1b4");
-        assert_eq!(result[37], "e1a5");
-        assert_eq!(result[38], "f1g2");
-        assert_eq!(result[39], "f1e2");
-        assert_eq!(result[40], "g1g2");
-        assert_eq!(result[41], "g1h2");
-        assert_eq!(result[42], "h1i3");
-        assert_eq!(result[43], "h1k2");
-        assert_eq!(result[44], "h1e2");
-        assert_eq!(result[45], "h1f4");
-        assert_eq!(result[46], "i1h2");
-        assert_eq!(result[47], "i1g3");
-        assert_eq!(result[48], "i1f4");
-        assert_eq!(result[49], "k1k2");
-        assert_eq!(result[50], "k1k3");
+impl fmt::Display for Hexchess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", render_text(self, &TextOptions { unicode: true, ..TextOptions::default() }));
+        }
+
+        let geometry = self.geometry();
+
+        write!(f, "{}", stringify_board(&self.board, &self.promoted, geometry))?;
+
+        if self.variant.has_drops() {
+            write!(f, "[{}]", stringify_pockets(&self.pockets))?;
+        }
+
+        write!(f, " {}", self.turn)?;
+
+        if geometry.has_castling() {
+            let rights: String = CASTLING_RIGHTS
+                .iter()
+                .zip(self.castling)
+                .filter_map(|(c, right)| right.then_some(*c))
+                .collect();
+
+            write!(f, " {}", match rights.is_empty() {
+                true => "-".to_string(),
+                false => rights,
+            })?;
+        }
+
+        write!(f, " {}", match self.ep {
+            Some(ep) => geometry.position_name(ep).unwrap_or_default(),
+            None => "-".to_string(),
+        })?;
+
+        if self.variant.check_limit().is_some() {
+            write!(f, " {}+{}", self.checks[0], self.checks[1])?;
+        }
+
+        write!(f, " {} {}", self.halfmove, self.fullmove)
     }
+}
 
-    #[test]
-    fn find_kings_by_color() {
-        let hexchess = Hexchess::init();
+/// test if a value is it's type's default, to leave it out when serializing
+#[cfg(feature = "serde")]
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
 
-        assert_eq!(hexchess.find_king(Color::Black), Some(h!("g10")));
-        assert_eq!(hexchess.find_king(Color::White), Some(h!("g1")));
+/// get the name of a color for messages
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black => "black",
+        Color::White => "white",
     }
+}
 
-    #[test]
-    fn test_get() {
-        let hexchess = Hexchess::init();
+/// parse the castling rights segment of fen
+fn parse_castling(source: &str, geometry: &BoardGeometry) -> Result<[bool; 4], String> {
+    let mut result = [false; 4];
 
-        assert_eq!(hexchess.get("g10"), Some(Piece::BlackKing));
-        assert_eq!(hexchess.get("g1"), Some(Piece::WhiteKing));
-        assert_eq!(hexchess.get("a4"), None);
-        assert_eq!(hexchess.get("whoops"), None);
+    if source == "-" {
+        return Ok(result);
     }
 
-    #[test]
-    fn get_color() {
-        let hexchess = Hexchess::init();
-        let results = hexchess.get_color(Color::Black);
+    for c in source.chars() {
+        match CASTLING_RIGHTS.iter().position(|right| *right == c) {
+            Some(i) if !result[i] && geometry.castling[i].is_some() => result[i] = true,
+            _ => return Err(format!("invalid castling rights: {}", source)),
+        }
+    }
 
-        assert_eq!(results.len(), 18);
-        assert_eq!(results[0], h!("f11"));
-        assert_eq!(results[1], h!("e10"));
-        assert_eq!(results[2], h!("f10"));
-        assert_eq!(results[3], h!("g10"));
-        assert_eq!(results[4], h!("d9"));
-        assert_eq!(results[5], h!("f9"));
-        assert_eq!(results[6], h!("h9"));
-        assert_eq!(results[7], h!("c8"));
-        assert_eq!(results[8], h!("i8"));
-        assert_eq!(results[9], h!("b7"));
-        assert_eq!(results[10], h!("c7"));
-        assert_eq!(results[11], h!("d7"));
-        assert_eq!(results[12], h!("e7"));
-        assert_eq!(results[13], h!("f7"));
-        assert_eq!(results[14], h!("g7"));
-        assert_eq!(results[15], h!("h7"));
-        assert_eq!(results[16], h!("i7"));
-        assert_eq!(results[17], h!("k7"));
+    Ok(result)
+}
+
+/// parse checks given by white and black, such as `2+0`
+fn parse_checks(source: &str, limit: u8) -> Result<[u8; 2], String> {
+    let checks = source
+        .split_once('+')
+        .and_then(|(white, black)| Some([white.parse::<u8>().ok()?, black.parse::<u8>().ok()?]));
+
+    match checks {
+        Some(checks) if checks.iter().all(|n| *n <= limit) => Ok(checks),
+        _ => Err(format!("invalid checks: {}", source)),
+    }
+}
+
+/// parse pieces in hand, the part of a bracketed pocket after it's opening bracket
+fn parse_pockets(source: &str) -> Result<[[u8; 5]; 2], String> {
+    let mut result = [[0u8; 5]; 2];
+
+    let pieces = match source.strip_suffix(']') {
+        Some(pieces) => pieces,
+        None => return Err(format!("invalid pockets: [{}", source)),
+    };
+
+    for c in pieces.chars() {
+        let piece = match Piece::try_from(c).ok().filter(|piece| drop_index(*piece).is_some()) {
+            Some(piece) => piece,
+            None => return Err(format!("invalid pockets: [{}", source)),
+        };
+
+        result[side(get_color(&piece))][drop_index(piece).unwrap()] += 1;
     }
 
-    mod is_check {
+    Ok(result)
+}
+
+/// format pieces in hand, white's then black's
+fn stringify_pockets(pockets: &[[u8; 5]; 2]) -> String {
+    let mut result = String::new();
+
+    for (color, pocket) in [Color::White, Color::Black].iter().zip(pockets) {
+        for (piece, count) in DROP_PIECES.iter().zip(pocket) {
+            let piece = match color {
+                Color::Black => swap_color(piece),
+                Color::White => *piece,
+            };
+
+            for _ in 0..*count {
+                result.push_str(&piece.to_string());
+            }
+        }
+    }
+
+    result
+}
+
+/// test if a position is passed over by a double step of either color
+fn is_en_passant_position(geometry: &BoardGeometry, position: u8) -> bool {
+    geometry.is_en_passant(position, Color::White) || geometry.is_en_passant(position, Color::Black)
+}
+
+/// find positions a pawn of a color could ever stand on, starting from an initial position
+fn pawn_reachable(initial: &Hexchess, color: Color) -> [bool; 91] {
+    let geometry = initial.geometry();
+    let [portside, starboard] = geometry.pawn_captures(color);
+    let directions: [Direction; 3] = [geometry.pawn_advance(color), portside, starboard];
+
+    let pawn = match color {
+        Color::Black => Piece::BlackPawn,
+        Color::White => Piece::WhitePawn,
+    };
+
+    let mut result = [false; 91];
+    let mut stack: Vec<u8> = (0u8..91u8).filter(|n| initial.board[*n as usize] == Some(pawn)).collect();
+
+    while let Some(position) = stack.pop() {
+        if result[position as usize] {
+            continue;
+        }
+
+        result[position as usize] = true;
+
+        for direction in directions {
+            stack.extend(geometry.step(position, direction));
+        }
+    }
+
+    result
+}
+
+/// parse the board segment of fen
+fn parse_board(source: &String, variant: Variant) -> Result<(Board, CellSet), String> {
+    let geometry = variant.geometry();
+    let mut arr = Board::new(geometry.cells);
+    let mut promoted = CellSet::default();
+    let cells: Vec<u8> = geometry.cells.iter().collect();
+    let cell = |n: u8| cells.get(n as usize).map(|n| *n as usize).ok_or("board overflow".to_string());
+    let mut black = false;
+    let mut white = false;
+    let mut fen_index: u8 = 0;
+
+    for (index, current) in source.chars().enumerate() {
+        match current {
+            '/' => continue,
+            '0' => continue,
+            '1' => match source.chars().nth(index as usize + 1) {
+                Some('0') | Some('1') => fen_index += 10,
+                _ => fen_index += 1,
+            },
+            '2' => fen_index += 2,
+            '3' => fen_index += 3,
+            '4' => fen_index += 4,
+            '5' => fen_index += 5,
+            '6' => fen_index += 6,
+            '7' => fen_index += 7,
+            '8' => fen_index += 8,
+            '9' => fen_index += 9,
+            'b' | 'B' | 'n' | 'N' | 'p' | 'P' | 'Q' | 'q' | 'r' | 'R' => {
+                // // it's safe to unwrap current because our match already checks for it
+                arr[cell(fen_index)?] = Some(to_piece(current).unwrap());
+
+                fen_index += 1;
+            }
+            'k' => {
+                if black && !variant.has_king_promotion() {
+                    return Err("multiple black kings".to_string());
+                }
+
+                arr[cell(fen_index)?] = Some(Piece::BlackKing);
+                black = true;
+                fen_index += 1;
+            }
+            'K' => {
+                if white && !variant.has_king_promotion() {
+                    return Err("multiple white kings".to_string());
+                }
+
+                arr[cell(fen_index)?] = Some(Piece::WhiteKing);
+                white = true;
+                fen_index += 1;
+            },
+            // promoted pieces are marked when the variant has drops, such as `Q~`
+            '~' if variant.has_drops() && index > 0 && "nbrqNBRQ".contains(source.chars().nth(index - 1).unwrap()) => {
+                promoted.insert(cell(fen_index - 1)? as u8);
+            },
+            _ => return Err(format!("invalid character at index {}: {}", index, current)),
+        }
+    }
+
+    if fen_index as usize != cells.len() {
+        return Err("board overflow".to_string());
+    }
+
+    Ok((arr, promoted))
+}
+
+/// format the board section of a fen
+fn stringify_board(board: &Board, promoted: &CellSet, geometry: &BoardGeometry) -> String {
+    let mut rows: Vec<String> = vec![];
+
+    for row in geometry.rows() {
+        let mut blank: u8 = 0;
+        let mut result = String::new();
+
+        for position in row {
+            match board[position as usize] {
+                None => {
+                    blank += 1;
+                },
+                Some(piece) => {
+                    if blank > 0 {
+                        result.push_str(&blank.to_string());
+                        blank = 0;
+                    }
+
+                    result.push_str(&piece.to_string());
+
+                    if promoted.contains(position) {
+                        result.push('~');
+                    }
+                },
+            };
+        }
+
+        if blank > 0 {
+            result.push_str(&blank.to_string());
+        }
+
+        rows.push(result);
+    }
+
+    rows.join("/")
+}
+
+/// convert character to piece
+fn to_piece(source: char) -> Result<Piece, &'static str> {
+    Piece::try_from(source).map_err(|_| "invalid_piece_character")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{h, s};
+    use crate::constants::INITIAL_POSITION;
+    use super::*;
+
+    mod apply {
         use super::*;
 
         #[test]
-        fn no_king() {
-            let hexchess = Hexchess::new();
+        fn test_applying_a_sequence_of_moves() {
+            let mut hexchess = Hexchess::init();
+            let _ = hexchess.apply("g4g6 f7g6 f5f7 g6f6");
 
-            assert_eq!(hexchess.is_check(), false);
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/pppp1pppp/5p5/11/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 3");
         }
 
         #[test]
-        fn not_in_check() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+        fn test_apply_with_invalid_san() {
+            let mut hexchess = Hexchess::init();
+            let result = hexchess.apply("whoops");
 
-            assert_eq!(hexchess.is_check(), false);
+            assert_eq!(result, Err("invalid san at index 0: whoops".to_string()));
         }
 
         #[test]
-        fn in_check() {
-            let hexchess = Hexchess::parse("K/3/5/7/9/5r5/11/11/11/11/11 w - 0 1").unwrap();
+        fn test_apply_with_illegal_move() {
+            let mut hexchess = Hexchess::init();
+            let result = hexchess.apply("g4g5 a6a5");
 
-            assert_eq!(hexchess.is_check(), true);
+            assert_eq!(result, Err("illegal move at index 1: a6a5".to_string()));
         }
     }
 
-    #[test]
-    fn is_checkmate() {
-        let mut hexchess = Hexchess::parse("K/3/5/3q3/2q6/11/11/11/11/11/11 b - 0 1").unwrap();
+    mod apply_move {
+        use super::*;
+
+        #[test]
+        fn sets_to_and_from_positions() {
+            let mut hexchess = Hexchess::init();
+            let _ = hexchess.apply_move(&s!("g4g5"));
+            let _ = hexchess.apply_move(&s!("e7e6"));
+            assert_eq!(hexchess.board[h!("g5")], Some(Piece::WhitePawn));
+            assert_eq!(hexchess.board[h!("g4")], None);
+            assert_eq!(hexchess.board[h!("e6")], Some(Piece::BlackPawn));
+            assert_eq!(hexchess.board[h!("e7")], None);
+        }
+
+        #[test]
+        fn clears_en_pasant_capture_white() {
+            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/ppppp1ppp/5P5/6p4/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w g6 0 2").unwrap();
+            let _ = hexchess.apply_move(&s!("f6g6"));
+
+            assert_eq!(hexchess.get("g5"), None);
+        }
+
+        #[test]
+        fn clears_en_passant_capture_black() {
+            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/pppp1pppp/5pP4/4PP5/11/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 2").unwrap();
+            let _ = hexchess.apply_move(&s!("f6g5"));
+
+            assert_eq!(hexchess.get("g6"), None);
+        }
+
+        #[test]
+        fn only_pawns_capture_en_passant() {
+            let mut hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/ppppp1ppp/11/5Pp4/4P1PB3/3P1B1P3/2P5P2/1PRNQBKNRP1 w g6 0 2").unwrap();
+            let _ = hexchess.apply_move(&s!("h4g6")); // <- bishop to en passant
+            
+            assert_eq!(hexchess.get("g5"), Some(Piece::BlackPawn));
+        }
+
+        #[test]
+        fn alternate_color_back_and_forth() {
+            let mut hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.turn, Color::White);
+            let _ = hexchess.apply_move(&s!("g4g5"));
+            assert_eq!(hexchess.turn, Color::Black);
+            let _ = hexchess.apply_move(&s!("e7e6"));
+            assert_eq!(hexchess.turn, Color::White);
+            let _ = hexchess.apply_move(&s!("f5f6"));
+            assert_eq!(hexchess.turn, Color::Black);
+        }
+
+        #[test]
+        fn sets_and_unsets_en_passant() {
+            let mut hexchess = Hexchess::init();
+
+            let _ = hexchess.apply_move(&s!("g4g6"));
+            assert_eq!(hexchess.ep, Some(h!("g5")));
+
+            let _ = hexchess.apply_move(&s!("e7e5"));
+            assert_eq!(hexchess.ep, Some(h!("e6")));
+
+            let _ = hexchess.apply_move(&s!("b1b2"));
+            assert_eq!(hexchess.ep, None);
+        }
+
+        #[test]
+        fn sets_halfmove_and_fullmove() {
+            let mut hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.fullmove, 1);
+
+            let _ = hexchess.apply_move(&s!("e4e5"));
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.fullmove, 1);
+
+            let _ = hexchess.apply_move(&s!("f7f6"));
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.fullmove, 2);
+
+            let _ = hexchess.apply_move(&s!("f3c6"));
+            assert_eq!(hexchess.halfmove, 1);
+            assert_eq!(hexchess.fullmove, 2);
+
+            let _ = hexchess.apply_move(&s!("i8h8"));
+            assert_eq!(hexchess.halfmove, 2);
+            assert_eq!(hexchess.fullmove, 3);
+
+            let _ = hexchess.apply_move(&s!("c6e10"));
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.fullmove, 3);
+        }
+
+        // promote white pieces
+        #[test]
+        fn white_and_black_promotions() {
+            let mut hexchess = Hexchess::parse("1/3/1P1P1/7/1P5P1/11/11/11/11/2p1p1p1p2/11 w - 0 1").unwrap();
+
+            let _ = hexchess.apply_move(&s!("c7c8r"));
+            assert_eq!(hexchess.board[h!("c8")], Some(Piece::WhiteRook));
+
+            let _ = hexchess.apply_move(&s!("c2c1r"));
+            assert_eq!(hexchess.board[h!("c1")], Some(Piece::BlackRook));
+
+            let _ = hexchess.apply_move(&s!("e9e10b"));
+            assert_eq!(hexchess.board[h!("e10")], Some(Piece::WhiteBishop));
+
+            let _ = hexchess.apply_move(&s!("e2e1b"));
+            assert_eq!(hexchess.board[h!("e1")], Some(Piece::BlackBishop));
+
+            let _ = hexchess.apply_move(&s!("g9g10q"));
+            assert_eq!(hexchess.board[h!("g10")], Some(Piece::WhiteQueen));
+
+            let _ = hexchess.apply_move(&s!("g2g1q"));
+            assert_eq!(hexchess.board[h!("g1")], Some(Piece::BlackQueen));
+
+            let _ = hexchess.apply_move(&s!("i7i8n"));
+            assert_eq!(hexchess.board[h!("i8")], Some(Piece::WhiteKnight));
+
+            let _ = hexchess.apply_move(&s!("i2i1n"));
+            assert_eq!(hexchess.board[h!("i1")], Some(Piece::BlackKnight));
+        }
+    
+        #[test]
+        fn errors_on_illegal_move() {
+            let mut hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.apply_move(&s!("a4a5")).is_err(), true);
+        }
+
+        #[test]
+        #[should_panic]
+        fn apply_move_unsafe_panics_on_empty_positions() {
+            let mut hexchess = Hexchess::init();
+
+            hexchess.apply_move_unsafe(&s!("a4a5"));
+        }
+
+        #[test]
+        fn try_apply_move_unsafe_errors_on_empty_positions() {
+            let mut hexchess = Hexchess::init();
+
+            assert_eq!(
+                hexchess.try_apply_move_unsafe(&s!("a4a5")).err(),
+                Some("cannot apply move from empty position: 47".to_string()),
+            );
+            assert_eq!(hexchess, Hexchess::init());
+        }
+
+        #[test]
+        fn try_apply_move_unsafe_errors_on_invalid_positions() {
+            let mut hexchess = Hexchess::init();
+
+            let san = San { from: 91, to: h!("f6"), promotion: None };
+            assert_eq!(hexchess.try_apply_move_unsafe(&san).err(), Some("invalid position index: 91".to_string()));
+
+            let san = San { from: h!("g4"), to: 200, promotion: None };
+            assert_eq!(hexchess.try_apply_move_unsafe(&san).err(), Some("invalid position index: 200".to_string()));
+            assert!(hexchess.apply_move(&San { from: 91, to: 92, promotion: None }).is_err());
+        }
+
+        #[test]
+        fn try_apply_move_unsafe_ignores_turn() {
+            let mut hexchess = Hexchess::init();
+
+            assert!(hexchess.try_apply_move_unsafe(&s!("f7f6")).is_ok());
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/pppp1pppp/5p5/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 2");
+        }
+    }
+
+    mod attack_map {
+        use super::*;
+
+        #[test]
+        fn empty_board() {
+            let hexchess = Hexchess::new();
+
+            assert_eq!(hexchess.attack_map(Color::White), [0; 91]);
+            assert_eq!(hexchess.attack_map(Color::Black), [0; 91]);
+        }
+
+        #[test]
+        fn pawns_attack_empty_positions() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/1P9 w - 0 1").unwrap();
+            let map = hexchess.attack_map(Color::White);
+
+            assert_eq!(map[h!("a1")], 1);
+            assert_eq!(map[h!("c2")], 1);
+            assert_eq!(map[h!("b2")], 0); // <- forward moves are not attacks
+            assert_eq!(map.iter().map(|n| *n as u32).sum::<u32>(), 2);
+        }
+
+        #[test]
+        fn counts_overlapping_attacks() {
+            let hexchess = Hexchess::init();
+            let white = hexchess.attack_map(Color::White);
+            let black = hexchess.attack_map(Color::Black);
+
+            assert_eq!(white[h!("f4")], 4); // <- c1 rook, d1 knight, h1 knight, i1 rook
+            assert_eq!(white[h!("f1")], 2); // <- friendly queen and king defend
+            assert_eq!(black[h!("f7")], 2); // <- e7 and g7 pawns defend
+            assert_eq!(
+                white.iter().map(|n| *n as u32).sum::<u32>(),
+                black.iter().map(|n| *n as u32).sum::<u32>(),
+            );
+        }
+    }
+
+    mod attackers_of {
+        use super::*;
+
+        #[test]
+        fn attackers_of_empty_position() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/4p1p4/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.attackers_of(h!("f5"), Color::Black), vec![h!("e5"), h!("g5")]);
+            assert_eq!(hexchess.attackers_of(h!("f4"), Color::White).len(), 0);
+        }
+
+        #[test]
+        fn defenders_of_friendly_piece() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5R5/5N5/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.attackers_of(h!("f5"), Color::White), vec![h!("f6")]);
+        }
+
+        #[test]
+        fn sliders_are_blocked() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5R5/5N5/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.attackers_of(h!("f4"), Color::White).len(), 0);
+        }
+    }
+
+    mod checkers {
+        use super::*;
+
+        #[test]
+        fn no_king() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5r5/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.checkers().len(), 0);
+        }
+
+        #[test]
+        fn not_in_check() {
+            let hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.checkers().len(), 0);
+        }
+
+        #[test]
+        fn single_check() {
+            let hexchess = Hexchess::parse("K/3/5/7/9/5r5/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.checkers(), vec![h!("f6")]);
+        }
+
+        #[test]
+        fn double_check() {
+            let hexchess = Hexchess::parse("K/3/5/4n2/9/5r5/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.checkers(), vec![h!("g8"), h!("f6")]);
+        }
+
+        #[test]
+        fn checkers_of_black_king() {
+            let hexchess = Hexchess::parse("k/3/5/7/9/5R5/11/11/11/11/11 b - 0 1").unwrap();
+
+            assert_eq!(hexchess.checkers(), vec![h!("f6")]);
+        }
+
+        #[test]
+        fn kings_that_are_not_royal() {
+            for variant in [Variant::Antichess, Variant::Dark] {
+                let hexchess = Hexchess::parse_variant("K/3/5/4n2/9/5r5/11/11/11/11/11 w - 0 1", variant).unwrap();
+
+                assert!(hexchess.checkers().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        let hexchess = Hexchess::init();
+        let clone = hexchess.clone();
+
+        assert_eq!(clone.board, hexchess.board);
+        assert_eq!(clone.ep, hexchess.ep);
+        assert_eq!(clone.turn, hexchess.turn);
+        assert_eq!(clone.halfmove, hexchess.halfmove);  
+        assert_eq!(clone.fullmove, hexchess.fullmove);
+    }
+
+    #[test]
+    fn test_apply_move_unsafe() {
+        let mut hexchess = Hexchess::init();
+
+        hexchess.apply_move_unsafe(&s!("b1b6")); // <- illegal pawn move
+
+        assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/1P9/5P5/4P1P4/3P1B1P3/2P2B2P2/2RNQBKNRP1 b - 0 1");
+    }
+
+    #[test]
+    fn test_current_moves() {
+        let hexchess = Hexchess::init();
+        let result = hexchess.current_moves().iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_eq!(result.len(), 51);
+        assert_eq!(result[0], "f5f6");
+        assert_eq!(result[1], "e4e5");
+        assert_eq!(result[2], "e4e6");
+        assert_eq!(result[3], "g4g5");
+        assert_eq!(result[4], "g4g6");
+        assert_eq!(result[5], "d3d4");
+        assert_eq!(result[6], "d3d5");
+        assert_eq!(result[7], "f3h2");
+        assert_eq!(result[8], "f3d2");
+        assert_eq!(result[9], "h3h4");
+        assert_eq!(result[10], "h3h5");
+        assert_eq!(result[11], "c2c3");
+        assert_eq!(result[12], "c2c4");
+        assert_eq!(result[13], "f2g3");
+        assert_eq!(result[14], "f2h4");
+        assert_eq!(result[15], "f2i5");
+        assert_eq!(result[16], "f2k6");
+        assert_eq!(result[17], "f2e3");
+        assert_eq!(result[18], "f2d4");
+        assert_eq!(result[19], "f2c5");
+        assert_eq!(result[20], "f2b6");
+        assert_eq!(result[21], "i2i3");
+        assert_eq!(result[22], "i2i4");
+        assert_eq!(result[23], "b1b2");
+        assert_eq!(result[24], "b1b3");
+        assert_eq!(result[25], "c1d2");
+        assert_eq!(result[26], "c1e3");
+        assert_eq!(result[27], "c1f4");
+        assert_eq!(result[28], "d1f4");
+        assert_eq!(result[29], "d1g2");
+        assert_eq!(result[30], "d1b2");
+        assert_eq!(result[31], "d1c3");
+        assert_eq!(result[32], "e1e2");
+        assert_eq!(result[33], "e1e3");
+        assert_eq!(result[34], "e1d2");
+        assert_eq!(result[35], "e1c3");
+        assert_eq!(result[36], "e1b4");
+        assert_eq!(result[37], "e1a5");
+        assert_eq!(result[38], "f1g2");
+        assert_eq!(result[39], "f1e2");
+        assert_eq!(result[40], "g1g2");
+        assert_eq!(result[41], "g1h2");
+        assert_eq!(result[42], "h1i3");
+        assert_eq!(result[43], "h1k2");
+        assert_eq!(result[44], "h1e2");
+        assert_eq!(result[45], "h1f4");
+        assert_eq!(result[46], "i1h2");
+        assert_eq!(result[47], "i1g3");
+        assert_eq!(result[48], "i1f4");
+        assert_eq!(result[49], "k1k2");
+        assert_eq!(result[50], "k1k3");
+    }
+
+    mod discovered_check_candidates {
+        use super::*;
+
+        #[test]
+        fn piece_blocking_friendly_rook() {
+            let hexchess = Hexchess::parse("k/3/5/3N3/9/11/11/11/5R5/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.discovered_check_candidates(), vec![h!("f8")]);
+        }
+
+        #[test]
+        fn piece_blocking_friendly_bishop() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5k5/11/4n6/11/3b7/11 b - 0 1").unwrap();
+
+            assert_eq!(hexchess.discovered_check_candidates().len(), 0);
+
+            let hexchess = Hexchess::parse("1/3/5/7/9/5K5/11/4n6/11/3b7/11 b - 0 1").unwrap();
+
+            assert_eq!(hexchess.discovered_check_candidates(), vec![h!("e4")]);
+        }
+
+        #[test]
+        fn hostile_blocker_is_not_a_candidate() {
+            let hexchess = Hexchess::parse("k/3/5/3n3/9/11/11/11/5R5/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.discovered_check_candidates().len(), 0);
+        }
+
+        #[test]
+        fn initial_position() {
+            let hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.discovered_check_candidates().len(), 0);
+        }
+    }
+
+    #[test]
+    fn find_kings_by_color() {
+        let hexchess = Hexchess::init();
+
+        assert_eq!(hexchess.find_king(Color::Black), Some(h!("g10")));
+        assert_eq!(hexchess.find_king(Color::White), Some(h!("g1")));
+    }
+
+    #[test]
+    fn test_get() {
+        let hexchess = Hexchess::init();
+
+        assert_eq!(hexchess.get("g10"), Some(Piece::BlackKing));
+        assert_eq!(hexchess.get("g1"), Some(Piece::WhiteKing));
+        assert_eq!(hexchess.get("a4"), None);
+        assert_eq!(hexchess.get("whoops"), None);
+    }
+
+    #[test]
+    fn get_color() {
+        let hexchess = Hexchess::init();
+        let results = hexchess.get_color(Color::Black);
+
+        assert_eq!(results.len(), 18);
+        assert_eq!(results[0], h!("f11"));
+        assert_eq!(results[1], h!("e10"));
+        assert_eq!(results[2], h!("f10"));
+        assert_eq!(results[3], h!("g10"));
+        assert_eq!(results[4], h!("d9"));
+        assert_eq!(results[5], h!("f9"));
+        assert_eq!(results[6], h!("h9"));
+        assert_eq!(results[7], h!("c8"));
+        assert_eq!(results[8], h!("i8"));
+        assert_eq!(results[9], h!("b7"));
+        assert_eq!(results[10], h!("c7"));
+        assert_eq!(results[11], h!("d7"));
+        assert_eq!(results[12], h!("e7"));
+        assert_eq!(results[13], h!("f7"));
+        assert_eq!(results[14], h!("g7"));
+        assert_eq!(results[15], h!("h7"));
+        assert_eq!(results[16], h!("i7"));
+        assert_eq!(results[17], h!("k7"));
+    }
+
+    mod editor {
+        use super::*;
+
+        #[test]
+        fn set_and_remove() {
+            let mut hexchess = Hexchess::new();
+
+            assert_eq!(hexchess.set("f6", Some(Piece::WhiteQueen)), Ok(()));
+            assert_eq!(hexchess.get("f6"), Some(Piece::WhiteQueen));
+
+            assert_eq!(hexchess.remove("f6"), Ok(()));
+            assert_eq!(hexchess.get("f6"), None);
+
+            assert_eq!(hexchess.set("x1", Some(Piece::WhiteQueen)), Err("invalid position: x1".to_string()));
+            assert_eq!(hexchess.remove("f12"), Err("invalid position: f12".to_string()));
+        }
+
+        #[test]
+        fn clear() {
+            let mut hexchess = Hexchess::init();
+
+            hexchess.apply_move(&s!("g4g6")).unwrap();
+            hexchess.clear();
+
+            assert_eq!(hexchess.to_string(), "1/3/5/7/9/11/11/11/11/11/11 b - 0 1");
+        }
+
+        #[test]
+        fn set_ep_and_turn() {
+            let mut hexchess = Hexchess::new();
+
+            hexchess.set_turn(Color::Black);
+            assert_eq!(hexchess.set_ep(Some("g5")), Ok(()));
+            assert_eq!(hexchess.to_string(), "1/3/5/7/9/11/11/11/11/11/11 b g5 0 1");
+
+            assert_eq!(hexchess.set_ep(None), Ok(()));
+            assert_eq!(hexchess.ep, None);
+
+            assert_eq!(hexchess.set_ep(Some("a1")), Err("illegal en passant position: a1".to_string()));
+            assert_eq!(hexchess.set_ep(Some("x1")), Err("invalid en passant position: x1".to_string()));
+        }
+    }
+
+    mod is_check {
+        use super::*;
+
+        #[test]
+        fn no_king() {
+            let hexchess = Hexchess::new();
+
+            assert_eq!(hexchess.is_check(), false);
+        }
+
+        #[test]
+        fn not_in_check() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_check(), false);
+        }
+
+        #[test]
+        fn in_check() {
+            let hexchess = Hexchess::parse("K/3/5/7/9/5r5/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_check(), true);
+        }
+    }
+
+    #[test]
+    fn is_checkmate() {
+        let mut hexchess = Hexchess::parse("K/3/5/3q3/2q6/11/11/11/11/11/11 b - 0 1").unwrap();
+
+        assert_eq!(hexchess.is_checkmate(), false);
+  
+        let _ = hexchess.apply_move(&s!("d7f9"));
+  
+        assert_eq!(hexchess.is_checkmate(), true);
+    }
+
+    mod is_legal {
+        use super::*;
+
+        #[test]
+        fn legal_move() {
+            let hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.is_legal(&s!("g4g5")), true);
+        }
+
+        #[test]
+        fn illegal_move() {
+            let hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.is_legal(&s!("b1b4")), false);
+        }
+
+        #[test]
+        fn illegal_move_out_of_turn() {
+            let mut hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.is_legal(&s!("g7g6")), false);
+
+            hexchess.turn = Color::Black;
+
+            assert_eq!(hexchess.is_legal(&s!("g7g6")), true);
+        }
+
+        #[test]
+        fn white_cannot_promote_on_blacks_positions() {
+            let hexchess = Hexchess::parse("1/3/5/7/p7p/R9R/11/11/11/11/rP7Pr w - 0 1").unwrap();
+
+            let b1b2 = San { from: h!("b1"), to: h!("b2"), promotion: None };
+            let b1b2q = San { from: h!("b1"), to: h!("b2"), promotion: Some(PromotionPiece::Queen) };
+            assert_eq!(hexchess.is_legal(&b1b2), true);
+            assert_eq!(hexchess.is_legal(&b1b2q), false);
+
+            let k1l1 = San { from: h!("k1"), to: h!("l1"), promotion: None };
+            let k1l1q = San { from: h!("k1"), to: h!("l1"), promotion: Some(PromotionPiece::Queen) };
+            assert_eq!(hexchess.is_legal(&k1l1), true);
+            assert_eq!(hexchess.is_legal(&k1l1q), false);
+        }
+
+        #[test]
+        fn black_cannot_promote_on_whites_positions() {
+            let hexchess = Hexchess::parse("1/3/5/7/p7p/R9R/11/11/11/11/rP7Pr b - 0 1").unwrap();
+
+            let b7a6 = San { from: h!("b7"), to: h!("a6"), promotion: None };
+            let b7a6q = San { from: h!("b7"), to: h!("a6"), promotion: Some(PromotionPiece::Queen) };
+            assert_eq!(hexchess.is_legal(&b7a6), true);
+            assert_eq!(hexchess.is_legal(&b7a6q), false);
+
+            let k7l6 = San { from: h!("k7"), to: h!("l6"), promotion: None };
+            let k7l6q = San { from: h!("k7"), to: h!("l6"), promotion: Some(PromotionPiece::Queen) };
+            assert_eq!(hexchess.is_legal(&k7l6), true);
+            assert_eq!(hexchess.is_legal(&k7l6q), false);
+        }
+
+        #[test]
+        fn pawn_must_promote_on_final_rank() {
+            let mut hexchess = Hexchess::parse("1/1P1/5/7/9/11/11/11/11/5p5/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_legal(&s!("f10f11")), false);
+            assert_eq!(hexchess.is_legal(&s!("f10f11q")), true);
+
+            hexchess.turn = Color::Black;
+
+            assert_eq!(hexchess.is_legal(&s!("f2f1")), false);
+            assert_eq!(hexchess.is_legal(&s!("f2f1q")), true);
+        }
+    }
+
+    #[test]
+    fn is_stalemate() {
+        let mut hexchess = Hexchess::parse("k/1P1/5/3K3/9/11/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(hexchess.is_stalemate(), false);
+  
+        let _ = hexchess.apply_move(&s!("f8f9"));
+  
+        assert_eq!(hexchess.is_stalemate(), true);
+    }
+
+    mod is_threatened {
+        use super::*;
+
+        #[test]
+        fn unattacked_position_is_not_threatened() {
+            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_threatened(h!("g10")), false);
+        }
+
+        #[test]
+        fn threatened_by_enemy_piece() {
+            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/6r4 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_threatened(h!("g10")), true);
+        }
+        
+        #[test]
+        fn not_threatened_by_friendly_piece() {
+            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/6R4 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.is_threatened(h!("g10")), false);
+        }
+
+        #[test]
+        fn  position_is_threatened_in_and_out_of_turn() {
+            let mut hexchess = Hexchess::parse("1/3/5/7/4q4/5K5/11/11/11/11/11 w - 0 1").unwrap();
+
+            hexchess.turn = Color::Black;
+            assert_eq!(hexchess.is_threatened(h!("f6")), true);
+
+            hexchess.turn = Color::White;
+            assert_eq!(hexchess.is_threatened(h!("f6")), true);
+        }
+
+        #[test]
+        fn unoccupied_position_is_not_threatened() {
+            let hexchess = Hexchess::new();
+
+            assert_eq!(hexchess.is_threatened(h!("f5")), false);
+        }
+    }
+
+    mod moves_from {
+        use super::*;
+
+        #[test]
+        fn returns_empty_vector_for_empty_position() {
+            let hexchess = Hexchess::init();
+
+            assert_eq!(hexchess.moves_from(h!("a4")).len(), 0);
+            assert_eq!(hexchess.moves_from_unsafe(h!("a4")).len(), 0);
+        }
+    }
+
+    mod parsing {
+        use crate::h;
+        use super::*;
+
+        #[test]
+        fn parse_strict() {
+            assert_eq!(Hexchess::parse_strict(INITIAL_POSITION), Ok(Hexchess::init()));
+
+            assert_eq!(
+                Hexchess::parse_strict("x/3/5/7/9/11/11/11/11/11/11 w - 0 1"),
+                Hexchess::parse("x/3/5/7/9/11/11/11/11/11/11 w - 0 1"),
+            );
+
+            assert_eq!(
+                Hexchess::parse_strict("1/3/5/7/9/11/5k5/11/5Q5/11/5K5 w - 0 1"),
+                Err("black king is in check with white to move".to_string()),
+            );
+
+            assert_eq!(
+                Hexchess::parse_strict("b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 1"),
+                Err("no pawn beyond en passant position: g5; en passant starting position is occupied: g5".to_string()),
+            );
+
+            assert_eq!(
+                Hexchess::parse_strict("P/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 1"),
+                Err("white pawn on promotion position: f11; too many white pawns: 10".to_string()),
+            );
+
+            assert_eq!(
+                Hexchess::parse_strict("1/3/5/7/9/11/11/11/11/11/11 w - 0 1"),
+                Err("missing white king; missing black king".to_string()),
+            );
+        }
+
+        #[test]
+        fn parse_strict_variant() {
+            let mini = Hexchess::init_variant(Variant::Mini);
+            assert_eq!(Hexchess::parse_strict_variant(&mini.to_string(), Variant::Mini), Ok(mini));
+
+            // kings aren't required when they may be captured
+            assert!(Hexchess::parse_strict_variant("1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1", Variant::Antichess).is_ok());
+
+            assert_eq!(
+                Hexchess::parse_strict_variant("1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1", Variant::Glinski),
+                Err("missing white king; missing black king".to_string()),
+            );
+        }
+
+        #[test]
+        fn empty_state() {
+            let hexchess = Hexchess::new();
+            
+            assert!(hexchess.board.pieces().iter().all(|&square| square.is_none()));
+            assert_eq!(hexchess.ep, None);
+            assert_eq!(hexchess.fullmove, 1);
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.turn, Color::White);
+        }
+
+        #[test]
+        fn initial_state() {
+            let hexchess = Hexchess::init();
+
+            assert!(hexchess.board.pieces().iter().eq([
+                Some(Piece::BlackBishop),
+                Some(Piece::BlackQueen),
+                Some(Piece::BlackBishop),
+                Some(Piece::BlackKing),
+                Some(Piece::BlackKnight),
+                None,
+                Some(Piece::BlackBishop),
+                None,
+                Some(Piece::BlackKnight),
+                Some(Piece::BlackRook),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Piece::BlackRook),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                Some(Piece::BlackPawn),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                Some(Piece::WhiteBishop),
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                None,
+                Some(Piece::WhiteBishop),
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                None,
+                None,
+                None,
+                Some(Piece::WhitePawn),
+                Some(Piece::WhiteRook),
+                Some(Piece::WhiteKnight),
+                Some(Piece::WhiteQueen),
+                Some(Piece::WhiteBishop),
+                Some(Piece::WhiteKing),
+                Some(Piece::WhiteKnight),
+                Some(Piece::WhiteRook),
+                Some(Piece::WhitePawn),
+                None,
+            ].iter()));
+        
+            assert_eq!(hexchess.turn, Color::White);
+            assert_eq!(hexchess.ep, None);
+            assert_eq!(hexchess.halfmove, 0);
+            assert_eq!(hexchess.fullmove, 1);
+        }
+
+        #[test]
+        fn empty_string() {
+            let hexchess = Hexchess::parse("");
+
+            assert!(hexchess.is_err());
+            assert_eq!(
+                hexchess.unwrap_err(),
+                "board not found"
+            );
+        }
+
+        #[test]
+        fn invalid() {
+            let hexchess = Hexchess::parse("whoops");
+
+            assert!(hexchess.is_err());
+        }
+
+        #[test]
+        fn turn_color() {
+            let white = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(white.turn, Color::White);
+
+            let black = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 b - 0 1").unwrap();
+
+            assert_eq!(black.turn, Color::Black);
+        }
+
+        #[test]
+        fn invalid_turn_color() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 x - 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(
+                hexchess.unwrap_err(),
+                "invalid turn color: x"
+            );
+        }
+
+        #[test]
+        fn missing_turn_color() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11").unwrap();
+
+            assert_eq!(hexchess.turn, Color::White);
+        }
+
+        #[test]
+        fn en_passant_black() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w e6 0 1");
+
+            assert_eq!(hexchess.unwrap().ep, Some(h!("e6")));
+        }
+
+        #[test]
+        fn en_passant_white() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w g5 0 1");
+
+            assert_eq!(hexchess.unwrap().ep, Some(h!("g5")));
+        }
+    
+        #[test]
+        fn invalid_en_passant() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w x 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(
+                hexchess.unwrap_err(),
+                "invalid en passant position: x"
+            );
+        }
+
+        #[test]
+        fn missing_en_passant() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w").unwrap();
+
+            assert_eq!(hexchess.ep, None);
+        }
+
+        #[test]
+        fn illegal_en_passant() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w a1 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(
+                hexchess.unwrap_err(),
+                "illegal en passant position: a1"
+            );
+        }
+
+        #[test]
+        fn invalid_halfmove() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - x 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(
+                hexchess.unwrap_err(),
+                "invalid halfmove: x"
+            );
+        }
+
+        #[test]
+        fn multiple_black_kings() {
+            let hexchess = Hexchess::parse("1/k1k/5/7/9/11/11/11/11/11/11 w - 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(hexchess.unwrap_err(), "multiple black kings");
+        }
+
+        #[test]
+        fn multiple_white_kings() {
+            let hexchess = Hexchess::parse("1/K1K/5/7/9/11/11/11/11/11/11 w - 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(hexchess.unwrap_err(), "multiple white kings");
+        }
+
+        #[test]
+        fn invalid_character() {
+            let hexchess = Hexchess::parse("x/3/5/7/9/11/11/11/11/11/11 w - 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(hexchess.unwrap_err(), "invalid character at index 0: x");
+        }
+
+        #[test]
+        fn board_overflow() {
+            let hexchess = Hexchess::parse("2/3/5/7/9/11/11/11/11/11/11 w - 0 1");
+
+            assert!(hexchess.is_err());
+            assert_eq!(hexchess.unwrap_err(), "board overflow");
+        }
+    
+        #[test]
+        fn missing_halfmove() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w -").unwrap();
+
+            assert_eq!(hexchess.halfmove, 0);
+        }
+
+        #[test]
+        fn invalid_fullmove() {
+            let invalid1 = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 x");
+            assert!(invalid1.is_err());
+            assert_eq!(invalid1.unwrap_err(), "invalid fullmove: x");
+
+            let invalid2 = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 0");
+            assert!(invalid2.is_err());
+            assert_eq!(invalid2.unwrap_err(), "invalid fullmove: 0");
+        }
+
+        #[test]
+        fn missing_fullmove() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0").unwrap();
+
+            assert_eq!(hexchess.fullmove, 1);
+        }
+
+        #[test]
+        fn fen_with_skip_1() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/1p9/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("b6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_2() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/2p8/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("c6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_3() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/3p7/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("d6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_4() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/4p6/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("e6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_5() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/5p5/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("f6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_6() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/6p4/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("g6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_7() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/7p3/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("h6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_8() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/8p2/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("i6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_9() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/9p1/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("k6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_10() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/p10/11/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("a6")]);
+        }
+
+        #[test]
+        fn fen_with_skip_11() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/p10/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("a5")]);
+        }
+
+    }
+
+    mod pinned {
+        use super::*;
+
+        #[test]
+        fn pinned_by_queen() {
+            let hexchess = Hexchess::parse("1/3/5/7/4K4/5R5/5q5/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.pinned(Color::White), vec![
+                Pin { direction: 6, pinned: h!("f6"), pinner: h!("f5") },
+            ]);
+
+            assert_eq!(hexchess.pinned(Color::Black), vec![]);
+        }
+
+        #[test]
+        fn pinned_on_diagonal() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5K5/11/4N6/11/3b7/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.pinned(Color::White), vec![
+                Pin { direction: 7, pinned: h!("e4"), pinner: h!("d2") },
+            ]);
+        }
+
+        #[test]
+        fn rooks_do_not_pin_diagonally() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5K5/11/4N6/11/3r7/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.pinned(Color::White), vec![]);
+        }
+
+        #[test]
+        fn two_blockers_are_not_pinned() {
+            let hexchess = Hexchess::parse("1/3/5/7/4K4/5R5/5P5/5q5/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.pinned(Color::White), vec![]);
+        }
+
+        #[test]
+        fn missing_king() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/5R5/5q5/11/11/11/11 w - 0 1").unwrap();
+
+            assert_eq!(hexchess.pinned(Color::White), vec![]);
+        }
+    }
+
+    mod self_check {
+        use super::*;
+
+        #[test]
+        fn cannot_step_out_of_a_pin() {
+            let hexchess = Hexchess::parse("1/3/5/7/4K4/5R5/5q5/11/11/11/11 w - 0 1").unwrap();
+
+            let moves = hexchess.moves_from(h!("f6"));
+            assert_eq!(moves.len(), 1);
+            assert_eq!(moves[0], s!("f6f5"));
+        }
+
+        // cannot self check on opponent's turn
+        #[test]
+        fn cannot_self_check_on_opponents_turn() {
+            let hexchess    = Hexchess::parse("1/3/5/7/4K4/5R5/5q5/11/11/11/11 b - 0 1").unwrap();
+            let moves = hexchess.moves_from(h!("f6"));
+
+            assert_eq!(moves.len(), 1);
+            assert_eq!(moves[0], s!("f6f5"));
+        }
+
+        // king cannot step into check
+        #[test]
+        fn king_cannot_step_into_check() {
+            let hexchess = Hexchess::parse("K/3/2q2/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+            let moves = hexchess.moves_from(h!("f11"));
+
+            assert_eq!(moves.len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_to_piece() {
+        assert_eq!(to_piece('b'), Ok(Piece::BlackBishop));
+        assert_eq!(to_piece('B'), Ok(Piece::WhiteBishop));
+        assert_eq!(to_piece('k'), Ok(Piece::BlackKing)); // <- not called during normal board parsing
+        assert_eq!(to_piece('K'), Ok(Piece::WhiteKing)); // <- not called during normal board parsing
+        assert_eq!(to_piece('n'), Ok(Piece::BlackKnight));
+        assert_eq!(to_piece('N'), Ok(Piece::WhiteKnight));
+        assert_eq!(to_piece('p'), Ok(Piece::BlackPawn));
+        assert_eq!(to_piece('P'), Ok(Piece::WhitePawn));
+        assert_eq!(to_piece('q'), Ok(Piece::BlackQueen));
+        assert_eq!(to_piece('Q'), Ok(Piece::WhiteQueen));
+        assert_eq!(to_piece('r'), Ok(Piece::BlackRook));
+        assert_eq!(to_piece('R'), Ok(Piece::WhiteRook));
+    }
+
+    #[test]
+    fn test_to_piece_invalid() {
+        assert_eq!(to_piece('x'), Err("invalid_piece_character"));
+        assert_eq!(to_piece('1'), Err("invalid_piece_character"));
+        assert_eq!(to_piece('/'), Err("invalid_piece_character"));
+        assert_eq!(to_piece(' '), Err("invalid_piece_character"));
+    }
+
+    mod standard_traits {
+        use std::collections::HashMap;
+        use super::*;
+
+        #[test]
+        fn from_str() {
+            assert_eq!(INITIAL_POSITION.parse::<Hexchess>(), Ok(Hexchess::init()));
+            assert_eq!(Hexchess::try_from(INITIAL_POSITION), Ok(Hexchess::init()));
+            assert!("whoops".parse::<Hexchess>().is_err());
+        }
+
+        #[test]
+        fn hash_key() {
+            let mut seen: HashMap<Hexchess, u8> = HashMap::new();
+
+            *seen.entry(Hexchess::init()).or_default() += 1;
+            *seen.entry(Hexchess::init()).or_default() += 1;
+            *seen.entry(Hexchess::new()).or_default() += 1;
+
+            assert_eq!(seen.len(), 2);
+            assert_eq!(seen[&Hexchess::init()], 2);
+        }
+
+        #[test]
+        fn index_by_cell() {
+            let mut hexchess = Hexchess::init();
+            let f5 = Cell::from_position("f5").unwrap();
+            let f6 = Cell::from_position("f6").unwrap();
+
+            assert_eq!(hexchess[f5], Some(Piece::WhitePawn));
+            assert_eq!(hexchess[f6], None);
+
+            hexchess[f6] = hexchess[f5].take();
+
+            assert_eq!(hexchess.get("f5"), None);
+            assert_eq!(hexchess.get("f6"), Some(Piece::WhitePawn));
+        }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn deserialize_checks_board_against_variant() {
+            let fields = |board: Board, variant: Variant| HexchessFields {
+                board,
+                castling: [false; 4],
+                checks: [0; 2],
+                ep: None,
+                fullmove: 1,
+                halfmove: 0,
+                pockets: [[0; 5]; 2],
+                promoted: CellSet::default(),
+                turn: Color::White,
+                variant,
+            };
+
+            let mini = Hexchess::init_variant(Variant::Mini);
 
-        assert_eq!(hexchess.is_checkmate(), false);
-  
-        let _ = hexchess.apply_move(&s!("d7f9"));
-  
-        assert_eq!(hexchess.is_checkmate(), true);
+            assert_eq!(Hexchess::try_from(fields(mini.board, Variant::Mini)), Ok(mini));
+            assert_eq!(Hexchess::try_from(fields(mini.board, Variant::Glinski)), Err("board does not fit the variant: 37 cells".to_string()));
+            assert_eq!(Hexchess::try_from(fields(Board::default(), Variant::Mini)), Err("board does not fit the variant: 91 cells".to_string()));
+        }
     }
 
-    mod is_legal {
+    mod to_string {
         use super::*;
 
         #[test]
-        fn legal_move() {
-            let hexchess = Hexchess::init();
+        fn empty_position() {
+            let hexchess = Hexchess::new();
 
-            assert_eq!(hexchess.is_legal(&s!("g4g5")), true);
+            assert_eq!(hexchess.to_string(), "1/3/5/7/9/11/11/11/11/11/11 w - 0 1");
         }
 
         #[test]
-        fn illegal_move() {
+        fn initial_position() {
             let hexchess = Hexchess::init();
 
-            assert_eq!(hexchess.is_legal(&s!("b1b4")), false);
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 1");
         }
 
         #[test]
-        fn illegal_move_out_of_turn() {
+        fn with_en_passant() {
             let mut hexchess = Hexchess::init();
 
-            assert_eq!(hexchess.is_legal(&s!("g7g6")), false);
-
-            hexchess.turn = Color::Black;
+            let _ = hexchess.apply_move(&s!("g4g6"));
 
-            assert_eq!(hexchess.is_legal(&s!("g7g6")), true);
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/6P4/5P5/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 1");
         }
 
         #[test]
-        fn white_cannot_promote_on_blacks_positions() {
-            let hexchess = Hexchess::parse("1/3/5/7/p7p/R9R/11/11/11/11/rP7Pr w - 0 1").unwrap();
+        fn alternate_diagram() {
+            let diagram = format!("{:#}", Hexchess::init());
 
-            let b1b2 = San { from: h!("b1"), to: h!("b2"), promotion: None };
-            let b1b2q = San { from: h!("b1"), to: h!("b2"), promotion: Some(PromotionPiece::Queen) };
-            assert_eq!(hexchess.is_legal(&b1b2), true);
-            assert_eq!(hexchess.is_legal(&b1b2q), false);
+            assert_eq!(diagram.lines().count(), 22);
+            assert_eq!(diagram.lines().next(), Some("                     11 ♝"));
+        }
+    }
 
-            let k1l1 = San { from: h!("k1"), to: h!("l1"), promotion: None };
-            let k1l1q = San { from: h!("k1"), to: h!("l1"), promotion: Some(PromotionPiece::Queen) };
-            assert_eq!(hexchess.is_legal(&k1l1), true);
-            assert_eq!(hexchess.is_legal(&k1l1q), false);
+    mod transformations {
+        use crate::hexchess::utils::playout_from;
+        use super::*;
+
+        /// play a deterministic pseudo-random game, collecting each position
+        fn positions(variant: Variant, seed: u64, plies: usize) -> Vec<Hexchess> {
+            let mut hexchess = Hexchess::init_variant(variant);
+            let mut result = vec![hexchess];
+
+            for san in playout_from(hexchess, seed, plies) {
+                hexchess.apply_move(&san).unwrap();
+                result.push(hexchess);
+            }
+
+            result
+        }
+
+        fn sorted(moves: Vec<San>) -> Vec<String> {
+            let mut result: Vec<String> = moves.iter().map(|san| san.to_string()).collect();
+            result.sort();
+            result
         }
 
         #[test]
-        fn black_cannot_promote_on_whites_positions() {
-            let hexchess = Hexchess::parse("1/3/5/7/p7p/R9R/11/11/11/11/rP7Pr b - 0 1").unwrap();
+        fn initial_position() {
+            let flipped = Hexchess::init().flip_colors().mirror();
 
-            let b7a6 = San { from: h!("b7"), to: h!("a6"), promotion: None };
-            let b7a6q = San { from: h!("b7"), to: h!("a6"), promotion: Some(PromotionPiece::Queen) };
-            assert_eq!(hexchess.is_legal(&b7a6), true);
-            assert_eq!(hexchess.is_legal(&b7a6q), false);
+            assert_eq!(flipped.board, Hexchess::init().board);
+            assert_eq!(flipped.turn, Color::Black);
+        }
 
-            let k7l6 = San { from: h!("k7"), to: h!("l6"), promotion: None };
-            let k7l6q = San { from: h!("k7"), to: h!("l6"), promotion: Some(PromotionPiece::Queen) };
-            assert_eq!(hexchess.is_legal(&k7l6), true);
-            assert_eq!(hexchess.is_legal(&k7l6q), false);
+        #[test]
+        fn involutions() {
+            for hexchess in positions(Variant::Glinski, 1, 40) {
+                assert_eq!(hexchess.flip_colors().flip_colors(), hexchess);
+                assert_eq!(hexchess.mirror().mirror(), hexchess);
+            }
         }
 
         #[test]
-        fn pawn_must_promote_on_final_rank() {
-            let mut hexchess = Hexchess::parse("1/1P1/5/7/9/11/11/11/11/5p5/11 w - 0 1").unwrap();
+        fn en_passant() {
+            let mut hexchess = Hexchess::init();
+            hexchess.apply_move(&s!("g4g6")).unwrap();
 
-            assert_eq!(hexchess.is_legal(&s!("f10f11")), false);
-            assert_eq!(hexchess.is_legal(&s!("f10f11q")), true);
+            assert_eq!(hexchess.flip_colors().ep, Some(h!("e6")));
+            assert_eq!(hexchess.mirror().ep, Some(h!("e5")));
+            assert_eq!(hexchess.flip_colors().validate(), Ok(()));
+            assert_eq!(hexchess.mirror().validate(), Ok(()));
+        }
 
-            hexchess.turn = Color::Black;
+        #[test]
+        fn symmetric_moves_and_attacks() {
+            for (variant, seed) in [(Variant::Glinski, 0), (Variant::Glinski, 1), (Variant::Glinski, 2), (Variant::Glinski, 3), (Variant::Shafran, 0), (Variant::Shafran, 1)] {
+                for mut hexchess in positions(variant, seed, 60) {
+                    // shafran's kings both start on the g-file, so it's castling has no rotated or mirrored counterpart
+                    if variant == Variant::Shafran {
+                        hexchess.castling = [false; 4];
+                    }
 
-            assert_eq!(hexchess.is_legal(&s!("f2f1")), false);
-            assert_eq!(hexchess.is_legal(&s!("f2f1q")), true);
-        }
-    }
+                    let geometry = hexchess.geometry();
+                    let flipped = hexchess.flip_colors();
+                    let mirrored = hexchess.mirror();
+                    let moves = hexchess.current_moves();
 
-    #[test]
-    fn is_stalemate() {
-        let mut hexchess = Hexchess::parse("k/1P1/5/3K3/9/11/11/11/11/11/11 w - 0 1").unwrap();
+                    assert_eq!(sorted(flipped.current_moves()), sorted(moves.iter().map(|san| san.flip_colors(geometry)).collect()));
+                    assert_eq!(sorted(mirrored.current_moves()), sorted(moves.iter().map(|san| san.mirror(geometry)).collect()));
+                    assert_eq!(flipped.is_check(), hexchess.is_check());
+                    assert_eq!(mirrored.is_check(), hexchess.is_check());
 
-        assert_eq!(hexchess.is_stalemate(), false);
-  
-        let _ = hexchess.apply_move(&s!("f8f9"));
-  
-        assert_eq!(hexchess.is_stalemate(), true);
+                    let white = hexchess.attack_map(Color::White);
+                    let black = flipped.attack_map(Color::Black);
+                    let mirrored_white = mirrored.attack_map(Color::White);
+
+                    for position in geometry.cells.iter() {
+                        assert_eq!(white[position as usize], black[geometry.rotate(position) as usize]);
+                        assert_eq!(white[position as usize], mirrored_white[geometry.mirror(position) as usize]);
+                    }
+                }
+            }
+        }
     }
 
-    mod is_threatened {
+    mod validate {
         use super::*;
 
-        #[test]
-        fn unattacked_position_is_not_threatened() {
-            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
-
-            assert_eq!(hexchess.is_threatened(h!("g10")), false);
+        fn errors(hexchess: &Hexchess) -> Vec<String> {
+            hexchess.validate().err().unwrap_or_default()
         }
 
         #[test]
-        fn threatened_by_enemy_piece() {
-            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/6r4 w - 0 1").unwrap();
+        fn valid_positions() {
+            assert_eq!(Hexchess::init().validate(), Ok(()));
 
-            assert_eq!(hexchess.is_threatened(h!("g10")), true);
-        }
-        
-        #[test]
-        fn not_threatened_by_friendly_piece() {
-            let hexchess = Hexchess::parse("1/2K/5/7/9/11/11/11/11/11/6R4 w - 0 1").unwrap();
+            let mut hexchess = Hexchess::init();
+            hexchess.apply("g4g6 e7e5").unwrap();
 
-            assert_eq!(hexchess.is_threatened(h!("g10")), false);
+            assert_eq!(hexchess.validate(), Ok(()));
         }
 
         #[test]
-        fn  position_is_threatened_in_and_out_of_turn() {
-            let mut hexchess = Hexchess::parse("1/3/5/7/4q4/5K5/11/11/11/11/11 w - 0 1").unwrap();
+        fn kings() {
+            assert_eq!(errors(&Hexchess::new()), vec!["missing white king", "missing black king"]);
 
-            hexchess.turn = Color::Black;
-            assert_eq!(hexchess.is_threatened(h!("f6")), true);
+            let mut hexchess = Hexchess::init();
+            hexchess.set("a1", Some(Piece::WhiteKing)).unwrap();
 
-            hexchess.turn = Color::White;
-            assert_eq!(hexchess.is_threatened(h!("f6")), true);
+            assert_eq!(errors(&hexchess), vec!["multiple white kings"]);
         }
 
         #[test]
-        fn unoccupied_position_is_not_threatened() {
-            let hexchess = Hexchess::new();
+        fn pawn_placement() {
+            let mut hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/5K5 w - 0 1").unwrap();
+            hexchess.set("f11", Some(Piece::BlackKing)).unwrap();
+            hexchess.set("f4", Some(Piece::WhitePawn)).unwrap();
+            hexchess.set("i1", Some(Piece::WhitePawn)).unwrap();
+            hexchess.set("a1", Some(Piece::WhitePawn)).unwrap();
+            hexchess.set("a6", Some(Piece::WhitePawn)).unwrap();
+            hexchess.set("g1", Some(Piece::BlackPawn)).unwrap();
+            hexchess.set("f8", Some(Piece::BlackPawn)).unwrap();
 
-            assert_eq!(hexchess.is_threatened(h!("f5")), false);
+            assert_eq!(errors(&hexchess), vec![
+                "white pawn on promotion position: a6",
+                "white pawn behind starting position: f4",
+                "white pawn behind starting position: i1",
+                "black pawn behind starting position: f8",
+                "black pawn on promotion position: g1",
+            ]);
         }
-    }
-
-    mod moves_from {
-        use super::*;
 
         #[test]
-        fn returns_empty_vector_for_empty_position() {
-            let hexchess = Hexchess::init();
+        fn piece_counts() {
+            let mut hexchess = Hexchess::init();
+            hexchess.set("a1", Some(Piece::WhitePawn)).unwrap();
 
-            assert_eq!(hexchess.moves_from(h!("a4")).len(), 0);
-            assert_eq!(hexchess.moves_from_unsafe(h!("a4")).len(), 0);
-        }
-    }
+            assert_eq!(errors(&hexchess), vec!["too many white pawns: 10"]);
 
-    mod parsing {
-        use crate::h;
-        use super::*;
+            let mut hexchess = Hexchess::init();
+            hexchess.set("f6", Some(Piece::BlackKnight)).unwrap();
 
-        #[test]
-        fn empty_state() {
-            let hexchess = Hexchess::new();
-            
-            assert!(hexchess.board.iter().all(|&square| square.is_none()));
-            assert_eq!(hexchess.ep, None);
-            assert_eq!(hexchess.fullmove, 1);
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.turn, Color::White);
+            assert_eq!(errors(&hexchess), vec!["too many black promoted pieces: 1"]);
+
+            hexchess.remove("b7").unwrap();
+
+            assert_eq!(hexchess.validate(), Ok(()));
         }
 
         #[test]
-        fn initial_state() {
-            let hexchess = Hexchess::init();
+        fn side_not_to_move_in_check() {
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/5k5/11/5Q5/11/5K5 w - 0 1").unwrap();
 
-            assert!(hexchess.board.iter().eq([
-                Some(Piece::BlackBishop),
-                Some(Piece::BlackQueen),
-                Some(Piece::BlackBishop),
-                Some(Piece::BlackKing),
-                Some(Piece::BlackKnight),
-                None,
-                Some(Piece::BlackBishop),
-                None,
-                Some(Piece::BlackKnight),
-                Some(Piece::BlackRook),
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(Piece::BlackRook),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                Some(Piece::BlackPawn),
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                Some(Piece::WhiteBishop),
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                None,
-                Some(Piece::WhiteBishop),
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                None,
-                None,
-                None,
-                Some(Piece::WhitePawn),
-                Some(Piece::WhiteRook),
-                Some(Piece::WhiteKnight),
-                Some(Piece::WhiteQueen),
-                Some(Piece::WhiteBishop),
-                Some(Piece::WhiteKing),
-                Some(Piece::WhiteKnight),
-                Some(Piece::WhiteRook),
-                Some(Piece::WhitePawn),
-                None,
-            ].iter()));
-        
-            assert_eq!(hexchess.turn, Color::White);
-            assert_eq!(hexchess.ep, None);
-            assert_eq!(hexchess.halfmove, 0);
-            assert_eq!(hexchess.fullmove, 1);
+            assert_eq!(errors(&hexchess), vec!["black king is in check with white to move"]);
+
+            let hexchess = Hexchess::parse("1/3/5/7/9/11/5k5/11/5Q5/11/5K5 b - 0 1").unwrap();
+
+            assert_eq!(hexchess.validate(), Ok(()));
         }
 
         #[test]
-        fn empty_string() {
-            let hexchess = Hexchess::parse("");
+        fn en_passant() {
+            let mut hexchess = Hexchess::init();
+            hexchess.set_ep(Some("g5")).unwrap();
 
-            assert!(hexchess.is_err());
-            assert_eq!(
-                hexchess.unwrap_err(),
-                "board not found"
-            );
+            assert_eq!(errors(&hexchess), vec!["en passant position inconsistent with turn: g5"]);
+
+            hexchess.set_turn(Color::Black);
+
+            assert_eq!(errors(&hexchess), vec![
+                "no pawn beyond en passant position: g5",
+                "en passant starting position is occupied: g5",
+            ]);
+
+            hexchess.apply_move_unsafe(&s!("g4g6"));
+            hexchess.set_turn(Color::Black);
+            hexchess.set_ep(Some("g5")).unwrap();
+            hexchess.set("c1", None).unwrap();
+            hexchess.set("g5", Some(Piece::WhiteRook)).unwrap();
+
+            assert_eq!(errors(&hexchess), vec!["en passant position is occupied: g5"]);
+        }
+    }
+
+    mod variant {
+        use super::*;
+        use crate::hexchess::utils::{get_color, to_position};
+
+        fn perft(hexchess: &Hexchess, depth: u8) -> usize {
+            if depth == 0 {
+                return 1;
+            }
+
+            hexchess.current_moves().iter().map(|san| {
+                let mut clone = *hexchess;
+                clone.apply_move_unsafe(san);
+                perft(&clone, depth - 1)
+            }).sum()
         }
 
         #[test]
-        fn invalid() {
-            let hexchess = Hexchess::parse("whoops");
-
-            assert!(hexchess.is_err());
+        fn defaults_to_glinski() {
+            assert_eq!(Hexchess::new().variant, Variant::Glinski);
+            assert_eq!(Hexchess::init(), Hexchess::init_variant(Variant::Glinski));
+            assert_eq!(Hexchess::parse_variant(INITIAL_POSITION, Variant::Glinski), Ok(Hexchess::init()));
         }
 
         #[test]
-        fn turn_color() {
-            let white = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
+        fn glinski_perft() {
+            let hexchess = Hexchess::init();
 
-            assert_eq!(white.turn, Color::White);
+            assert_eq!(perft(&hexchess, 1), 51);
+            assert_eq!(perft(&hexchess, 2), 2586);
 
-            let black = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 b - 0 1").unwrap();
+            let hexchess = Hexchess::parse("b/qbk/n1b1n/r5r/pppp1pppp/5p5/11/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 3").unwrap();
 
-            assert_eq!(black.turn, Color::Black);
+            assert_eq!(perft(&hexchess, 1), 58);
+            assert_eq!(perft(&hexchess, 2), 2898);
         }
 
         #[test]
-        fn invalid_turn_color() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 x - 0 1");
+        fn mccooey_initial_position() {
+            let hexchess = Hexchess::init_variant(Variant::McCooey);
 
-            assert!(hexchess.is_err());
-            assert_eq!(
-                hexchess.unwrap_err(),
-                "invalid turn color: x"
-            );
+            assert_eq!(hexchess.to_string(), "b/qbk/rnbnr/ppppppp/9/11/11/5P5/4PBP4/3PNBNP3/2PRQBKRP2 w - 0 1");
+            assert_eq!(hexchess.validate(), Ok(()));
+            assert_eq!(perft(&hexchess, 1), 31);
+            assert_eq!(perft(&hexchess, 2), 947);
         }
 
         #[test]
-        fn missing_turn_color() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11").unwrap();
+        fn mccooey_pawns() {
+            let mut hexchess = Hexchess::init_variant(Variant::McCooey);
 
-            assert_eq!(hexchess.turn, Color::White);
-        }
+            // the f-file pawn may not double step
+            assert_eq!(hexchess.moves_from(h!("f4")), vec![s!("f4f5")]);
 
-        #[test]
-        fn en_passant_black() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w e6 0 1");
+            hexchess.set("d4", Some(Piece::BlackPawn)).unwrap();
+            hexchess.apply("c1c3").unwrap();
+            assert_eq!(hexchess.ep, Some(h!("c2")));
 
-            assert_eq!(hexchess.unwrap().ep, Some(h!("e6")));
+            // pawns capture diagonally, including en passant
+            hexchess.apply("d4c2").unwrap();
+            assert_eq!(hexchess.get("c2"), Some(Piece::BlackPawn));
+            assert_eq!(hexchess.get("c3"), None);
         }
 
         #[test]
-        fn en_passant_white() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w g5 0 1");
+        fn parse_variant_fen() {
+            let hexchess = Hexchess::parse_variant("b/qbk/rnbnr/ppppppp/9/11/11/5P5/4PBP4/3PNBNP3/2PRQBKRP2 w - 0 1", Variant::McCooey).unwrap();
 
-            assert_eq!(hexchess.unwrap().ep, Some(h!("g5")));
+            assert_eq!(hexchess, Hexchess::init_variant(Variant::McCooey));
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/11/11/11/11/11/11 w c2 0 1", Variant::McCooey).unwrap().ep, Some(h!("c2")));
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/11/11/11/11/11/11 w f5 0 1", Variant::McCooey), Err("illegal en passant position: f5".to_string()));
         }
-    
-        #[test]
-        fn invalid_en_passant() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w x 0 1");
 
-            assert!(hexchess.is_err());
-            assert_eq!(
-                hexchess.unwrap_err(),
-                "invalid en passant position: x"
-            );
-        }
+        /// count moves without the engine's geometry, by stepping through cube
+        /// coordinates of shafran's cells. castling, en passant and promotion
+        /// can't happen within the plies this is used for, so they're left out.
+        fn shafran_cube_perft(board: Board, turn: Color, depth: u8) -> usize {
+            let cube = |position: u8| -> (i8, i8, i8) {
+                let name = to_position(&position);
+                let q = "abcdefghikl".find(&name[..1]).unwrap() as i8 - 5;
+                let rank = name[1..].parse::<i8>().unwrap();
+                let r = 5 - q.max(0) - (rank - 1);
+                (q, r, -q - r)
+            };
 
-        #[test]
-        fn missing_en_passant() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w").unwrap();
+            let cells: Vec<(u8, (i8, i8, i8))> = (0u8..91u8)
+                .filter(|n| !to_position(n).starts_with(['a', 'l']) && &to_position(n)[1..] != "1")
+                .map(|n| (n, cube(n)))
+                .collect();
 
-            assert_eq!(hexchess.ep, None);
-        }
+            let at = |target: (i8, i8, i8)| cells.iter().find(|(_, c)| *c == target).map(|(n, _)| *n);
 
-        #[test]
-        fn illegal_en_passant() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w a1 0 1");
+            let orthogonal: [(i8, i8, i8); 6] = [(0, -1, 1), (1, -1, 0), (1, 0, -1), (0, 1, -1), (-1, 1, 0), (-1, 0, 1)];
+            let diagonal: [(i8, i8, i8); 6] = [(1, -2, 1), (2, -1, -1), (1, 1, -2), (-1, 2, -1), (-2, 1, 1), (-1, -1, 2)];
+            let knight: [(i8, i8, i8); 12] = [
+                (1, -3, 2), (2, -3, 1), (3, -2, -1), (3, -1, -2), (2, 1, -3), (1, 2, -3),
+                (-1, 3, -2), (-2, 3, -1), (-3, 2, 1), (-3, 1, 2), (-2, -1, 3), (-1, -2, 3),
+            ];
 
-            assert!(hexchess.is_err());
-            assert_eq!(
-                hexchess.unwrap_err(),
-                "illegal en passant position: a1"
-            );
-        }
+            let add = |from: u8, (q, r, s): (i8, i8, i8)| {
+                let (a, b, c) = cells.iter().find(|(n, _)| *n == from).unwrap().1;
+                at((a + q, b + r, c + s))
+            };
 
-        #[test]
-        fn invalid_halfmove() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - x 1");
+            let initial = Hexchess::init_variant(Variant::Shafran).board;
+
+            let moves = |board: &Board, color: Color| -> Vec<(u8, u8)> {
+                let mut result = vec![];
+                let is_enemy = |to: u8| board[to as usize].is_some_and(|piece| get_color(&piece) != color);
+
+                for from in (0u8..91u8).filter(|n| board[*n as usize].is_some_and(|piece| get_color(&piece) == color)) {
+                    let piece = board[from as usize].unwrap();
+
+                    let (slides, steps, range): (Vec<_>, Vec<_>, i8) = match piece {
+                        Piece::WhiteKing | Piece::BlackKing => ([orthogonal, diagonal].concat(), vec![], 1),
+                        Piece::WhiteQueen | Piece::BlackQueen => ([orthogonal, diagonal].concat(), vec![], 10),
+                        Piece::WhiteRook | Piece::BlackRook => (orthogonal.to_vec(), vec![], 10),
+                        Piece::WhiteBishop | Piece::BlackBishop => (diagonal.to_vec(), vec![], 10),
+                        Piece::WhiteKnight | Piece::BlackKnight => (vec![], knight.to_vec(), 0),
+                        Piece::WhitePawn | Piece::BlackPawn => {
+                            let (forward, captures) = match color {
+                                Color::White => (orthogonal[0], [orthogonal[1], orthogonal[5]]),
+                                Color::Black => (orthogonal[3], [orthogonal[2], orthogonal[4]]),
+                            };
+
+                            if let Some(one) = add(from, forward).filter(|to| board[*to as usize].is_none()) {
+                                result.push((from, one));
+
+                                if initial[from as usize] == Some(piece) {
+                                    if let Some(two) = add(one, forward).filter(|to| board[*to as usize].is_none()) {
+                                        result.push((from, two));
+                                    }
+                                }
+                            }
 
-            assert!(hexchess.is_err());
-            assert_eq!(
-                hexchess.unwrap_err(),
-                "invalid halfmove: x"
-            );
-        }
+                            result.extend(captures.iter().filter_map(|direction| add(from, *direction)).filter(|to| is_enemy(*to)).map(|to| (from, to)));
+
+                            continue;
+                        },
+                    };
+
+                    for direction in slides {
+                        let mut position = from;
+
+                        for _ in 0..range {
+                            match add(position, direction) {
+                                Some(to) if board[to as usize].is_none() => {
+                                    result.push((from, to));
+                                    position = to;
+                                },
+                                Some(to) => {
+                                    if is_enemy(to) {
+                                        result.push((from, to));
+                                    }
+
+                                    break;
+                                },
+                                None => break,
+                            }
+                        }
+                    }
 
-        #[test]
-        fn multiple_black_kings() {
-            let hexchess = Hexchess::parse("1/k1k/5/7/9/11/11/11/11/11/11 w - 0 1");
+                    result.extend(steps.iter().filter_map(|step| add(from, *step)).filter(|to| board[*to as usize].is_none() || is_enemy(*to)).map(|to| (from, to)));
+                }
 
-            assert!(hexchess.is_err());
-            assert_eq!(hexchess.unwrap_err(), "multiple black kings");
+                result
+            };
+
+            if depth == 0 {
+                return 1;
+            }
+
+            moves(&board, turn).iter().map(|(from, to)| {
+                let mut next = board;
+                next[*to as usize] = next[*from as usize].take();
+
+                let opponent = match turn {
+                    Color::White => Color::Black,
+                    Color::Black => Color::White,
+                };
+
+                let king = (0u8..91u8).find(|n| next[*n as usize] == Some(match turn {
+                    Color::White => Piece::WhiteKing,
+                    Color::Black => Piece::BlackKing,
+                }));
+
+                match moves(&next, opponent).iter().any(|(_, to)| Some(*to) == king) {
+                    true => 0,
+                    false => shafran_cube_perft(next, opponent, depth - 1),
+                }
+            }).sum()
         }
 
         #[test]
-        fn multiple_white_kings() {
-            let hexchess = Hexchess::parse("1/K1K/5/7/9/11/11/11/11/11/11 w - 0 1");
+        fn shafran_initial_position() {
+            let hexchess = Hexchess::init_variant(Variant::Shafran);
 
-            assert!(hexchess.is_err());
-            assert_eq!(hexchess.unwrap_err(), "multiple white kings");
+            assert_eq!(hexchess.to_string(), "b/bpk/qp1pb/np3pn/rp5pr/p7p/9/9/PPPPPPPPP/RNQBBKBNR w KQkq - 0 1");
+            assert_eq!(hexchess.castling, [true; 4]);
+            assert_eq!(hexchess.validate(), Ok(()));
+            assert_eq!(perft(&hexchess, 1), 39);
+            assert_eq!(perft(&hexchess, 2), 1428);
+            assert_eq!(shafran_cube_perft(hexchess.board, Color::White, 1), 39);
+            assert_eq!(shafran_cube_perft(hexchess.board, Color::White, 2), 1428);
+
+            // black's setup reflects white's, so it has the same moves
+            let mut black = hexchess;
+            black.turn = Color::Black;
+            assert_eq!(perft(&black, 1), 39);
+            assert_eq!(perft(&hexchess.mirror(), 2), 1428);
         }
 
-        #[test]
-        fn invalid_character() {
-            let hexchess = Hexchess::parse("x/3/5/7/9/11/11/11/11/11/11 w - 0 1");
+        fn shafran_castling_position() -> Hexchess {
+            let mut hexchess = Hexchess::new_variant(Variant::Shafran);
 
-            assert!(hexchess.is_err());
-            assert_eq!(hexchess.unwrap_err(), "invalid character at index 0: x");
+            for (position, piece) in [("a1", "R"), ("f1", "K"), ("i1", "R"), ("e10", "k")] {
+                hexchess.set(position, Some(piece.parse().unwrap())).unwrap();
+            }
+
+            hexchess.castling = [true, true, false, false];
+            hexchess
         }
 
         #[test]
-        fn board_overflow() {
-            let hexchess = Hexchess::parse("2/3/5/7/9/11/11/11/11/11/11 w - 0 1");
+        fn shafran_castling() {
+            let mut hexchess = shafran_castling_position();
 
-            assert!(hexchess.is_err());
-            assert_eq!(hexchess.unwrap_err(), "board overflow");
+            assert_eq!(hexchess.to_string(), "k/3/5/7/9/9/9/9/9/R4K2R w KQ - 0 1");
+
+            let moves = hexchess.moves_from(h!("g2"));
+            assert!(moves.contains(&hexchess.geometry().parse_san("f1i1").unwrap()));
+            assert!(moves.contains(&hexchess.geometry().parse_san("f1a1").unwrap()));
+
+            hexchess.apply("f1i1").unwrap();
+            assert_eq!(hexchess.get("h1"), Some(Piece::WhiteKing));
+            assert_eq!(hexchess.get("g1"), Some(Piece::WhiteRook));
+            assert_eq!(hexchess.get("i1"), None);
+            assert_eq!(hexchess.to_string(), "k/3/5/7/9/9/9/9/9/R5RK1 b - - 1 1");
+
+            let mut hexchess = shafran_castling_position();
+            hexchess.apply("f1d1").unwrap();
+            assert_eq!(hexchess.get("d1"), Some(Piece::WhiteKing));
+            assert_eq!(hexchess.get("e1"), None);
+            assert_eq!(hexchess.get("a1"), Some(Piece::WhiteRook));
         }
-    
+
         #[test]
-        fn missing_halfmove() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w -").unwrap();
+        fn shafran_castling_rights() {
+            let mut hexchess = shafran_castling_position();
 
-            assert_eq!(hexchess.halfmove, 0);
+            hexchess.apply("i1i2 e10e9 i2i1 e9e10").unwrap();
+            assert_eq!(hexchess.castling, [false, true, false, false]);
+            assert!(hexchess.apply("f1i1").is_err());
+
+            // castling through an attacked position
+            let mut hexchess = shafran_castling_position();
+            hexchess.set("g8", Some(Piece::BlackRook)).unwrap();
+            assert!(hexchess.apply("f1i1").is_err());
+            assert!(hexchess.apply("f1a1").is_ok());
+
+            // castling path must be empty
+            let mut hexchess = shafran_castling_position();
+            hexchess.set("b1", Some(Piece::WhiteKnight)).unwrap();
+            assert!(hexchess.apply("f1a1").is_err());
+
+            assert_eq!(Hexchess::parse_variant("k/3/5/7/9/9/9/9/9/R4K2R w KK - 0 1", Variant::Shafran), Err("invalid castling rights: KK".to_string()));
+
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/9/9/9/9/R4K3 w KQ - 0 1", Variant::Shafran).unwrap();
+            hexchess.set("e9", Some(Piece::WhiteQueen)).unwrap();
+            assert!(hexchess.validate().unwrap_err().contains(&"castling right without king and rook in place: K".to_string()));
         }
 
         #[test]
-        fn invalid_fullmove() {
-            let invalid1 = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 x");
-            assert!(invalid1.is_err());
-            assert_eq!(invalid1.unwrap_err(), "invalid fullmove: x");
+        fn board_must_fit_variant() {
+            let mut hexchess = Hexchess::init_variant(Variant::Mini);
+            hexchess.variant = Variant::Glinski;
 
-            let invalid2 = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0 0");
-            assert!(invalid2.is_err());
-            assert_eq!(invalid2.unwrap_err(), "invalid fullmove: 0");
+            assert_eq!(hexchess.validate(), Err(vec!["board does not fit the variant: 37 cells".to_string()]));
         }
 
         #[test]
-        fn missing_fullmove() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/11/11/11/11/11 w - 0").unwrap();
+        fn mini_initial_position() {
+            let hexchess = Hexchess::init_variant(Variant::Mini);
 
-            assert_eq!(hexchess.fullmove, 1);
+            assert_eq!(hexchess.to_string(), "b/qpk/np1pn/rp3pr/p5p/PPPPPPP/RNQBKNR w - 0 1");
+            assert_eq!(hexchess.validate(), Ok(()));
+            assert_eq!(hexchess.get("a1"), Some(Piece::WhiteRook));
+            assert_eq!(hexchess.get("d7"), Some(Piece::BlackBishop));
+            assert_eq!(hexchess.board.len(), 37);
+            assert_eq!(perft(&hexchess, 1), 15);
+            assert_eq!(perft(&hexchess, 2), 161);
+
+            let mut black = hexchess;
+            black.turn = Color::Black;
+            assert_eq!(perft(&black, 1), 15);
+            assert_eq!(perft(&hexchess.mirror(), 2), 161);
         }
 
         #[test]
-        fn fen_with_skip_1() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/1p9/11/11/11/11/11 w - 0 1").unwrap();
+        fn mini_pawns() {
+            let mut hexchess = Hexchess::init_variant(Variant::Mini);
 
-            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("b6")]);
+            assert!(hexchess.apply("b2b4").is_err()); // <- no double steps
+            assert!(hexchess.apply("a2a3").is_err()); // <- blocked by black's pawn
+            hexchess.apply("b2b3").unwrap();
+            assert_eq!(hexchess.ep, None);
+
+            let mut hexchess = Hexchess::new_variant(Variant::Mini);
+
+            for (position, piece) in [("e1", "K"), ("d6", "P"), ("a4", "k")] {
+                hexchess.set(position, Some(piece.parse().unwrap())).unwrap();
+            }
+
+            hexchess.apply("d6d7q").unwrap();
+            assert_eq!(hexchess.get("d7"), Some(Piece::WhiteQueen));
+            assert_eq!(hexchess.set("h1", Some(Piece::WhitePawn)), Err("invalid position: h1".to_string()));
         }
 
         #[test]
-        fn fen_with_skip_2() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/2p8/11/11/11/11/11 w - 0 1").unwrap();
+        fn crazyhouse_fen() {
+            let hexchess = Hexchess::init_variant(Variant::Crazyhouse);
 
-            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("c6")]);
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1[] w - 0 1");
+            assert_eq!(hexchess.pockets, [[0; 5]; 2]);
+
+            let hexchess = Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[QPPnp] b - 0 1", Variant::Crazyhouse).unwrap();
+
+            assert_eq!(hexchess.pockets, [[2, 0, 0, 0, 1], [1, 1, 0, 0, 0]]);
+            assert_eq!(hexchess.to_string(), "1/3/5/7/9/2k8/11/11/11/5K5/11[PPQpn] b - 0 1");
+            assert_eq!(hexchess.flip_colors().pockets, [[1, 1, 0, 0, 0], [2, 0, 0, 0, 1]]);
+
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[Kk] w - 0 1", Variant::Crazyhouse), Err("invalid pockets: [Kk]".to_string()));
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[Q w - 0 1", Variant::Crazyhouse), Err("invalid pockets: [Q".to_string()));
+            assert!(Hexchess::parse("1/3/5/7/9/2k8/11/11/11/5K5/11[Q] w - 0 1").is_err());
+
+            let hexchess = Hexchess::parse_variant("b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1[Q] w - 0 1", Variant::Crazyhouse).unwrap();
+            assert_eq!(hexchess.validate(), Err(vec!["too many pieces on the board and in hand: 35".to_string()]));
         }
 
         #[test]
-        fn fen_with_skip_3() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/3p7/11/11/11/11/11 w - 0 1").unwrap();
+        fn crazyhouse_captures() {
+            let mut hexchess = Hexchess::init_variant(Variant::Crazyhouse);
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("d6")]);
+            hexchess.apply("g4g6 f7g6").unwrap();
+            assert_eq!(hexchess.pockets, [[0; 5], [1, 0, 0, 0, 0]]);
+
+            hexchess.apply("f5f7 g6f6").unwrap(); // <- en passant
+            assert_eq!(hexchess.pockets, [[0; 5], [2, 0, 0, 0, 0]]);
+            assert_eq!(hexchess.validate(), Ok(()));
+
+            // captures don't fill hands in other variants
+            let mut hexchess = Hexchess::init();
+            hexchess.apply("g4g6 f7g6").unwrap();
+            assert_eq!(hexchess.pockets, [[0; 5]; 2]);
         }
 
         #[test]
-        fn fen_with_skip_4() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/4p6/11/11/11/11/11 w - 0 1").unwrap();
+        fn crazyhouse_promoted_pieces() {
+            let mut hexchess = Hexchess::parse_variant("1/qP1/5/7/9/2k8/11/11/11/5K5/11[] w - 0 1", Variant::Crazyhouse).unwrap();
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("e6")]);
+            hexchess.apply("f10f11q").unwrap();
+            assert_eq!(hexchess.to_string(), "Q~/q2/5/7/9/2k8/11/11/11/5K5/11[] b - 0 1");
+            assert_eq!(Hexchess::parse_variant(&hexchess.to_string(), Variant::Crazyhouse), Ok(hexchess));
+
+            // captured promoted pieces return to hand as pawns
+            hexchess.apply("e10f11").unwrap();
+            assert_eq!(hexchess.to_string(), "q/3/5/7/9/2k8/11/11/11/5K5/11[p] w - 0 2");
+            assert!(hexchess.promoted.is_empty());
+
+            // promoted pieces stay marked as they move
+            let mut hexchess = Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/R~10[] w - 0 1", Variant::Crazyhouse).unwrap();
+
+            hexchess.apply("a1a2").unwrap();
+            assert_eq!(hexchess.to_string(), "1/3/5/7/9/2k8/11/11/11/R~4K5/11[] b - 1 1");
+
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/P~10[] w - 0 1", Variant::Crazyhouse), Err("invalid character at index 28: ~".to_string()));
+            assert_eq!(Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/R~10 w - 0 1", Variant::Glinski), Err("invalid character at index 28: ~".to_string()));
         }
 
         #[test]
-        fn fen_with_skip_5() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/5p5/11/11/11/11/11 w - 0 1").unwrap();
+        fn crazyhouse_drops() {
+            let mut hexchess = Hexchess::parse_variant("1/3/5/7/9/2k8/11/11/11/5K5/11[PN] w - 0 1", Variant::Crazyhouse).unwrap();
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("f6")]);
+            let drops = hexchess.drop_moves();
+
+            // 89 empty cells for the knight, less 22 promotion cells for the pawn
+            assert_eq!(drops.len(), 89 + 89 - 22);
+            assert!(!drops.contains(&s!("P@f11")));
+            assert!(!drops.contains(&s!("P@f1")));
+            assert!(drops.contains(&s!("P@f6")));
+            assert_eq!(hexchess.current_moves().len(), hexchess.moves_from(h!("f2")).len() + drops.len());
+
+            hexchess.apply("N@f6").unwrap();
+            assert_eq!(hexchess.get("f6"), Some(Piece::WhiteKnight));
+            assert_eq!(hexchess.pockets, [[1, 0, 0, 0, 0], [0; 5]]);
+            assert_eq!(hexchess.turn, Color::Black);
+            assert!(hexchess.drop_moves().is_empty());
+
+            assert!(hexchess.apply("P@f7").is_err()); // <- black has no pawn in hand
+            assert_eq!(
+                hexchess.try_apply_move_unsafe(&s!("P@f6")).err(),
+                Some(format!("cannot drop onto occupied position: {}", h!("f6"))),
+            );
+
+            // drops are not moves in other variants
+            assert!(Hexchess::init().apply("P@f6").is_err());
         }
 
         #[test]
-        fn fen_with_skip_6() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/6p4/11/11/11/11/11 w - 0 1").unwrap();
+        fn crazyhouse_checkmate() {
+            let mut hexchess = Hexchess::parse_variant("K/3/5/3q3/2q6/11/11/11/11/11/11[N] b - 0 1", Variant::Crazyhouse).unwrap();
+            hexchess.apply("d7f9").unwrap();
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("g6")]);
+            // the king can't escape, but a drop blocks the check
+            assert!(hexchess.is_check());
+            assert!(!hexchess.is_checkmate());
+            assert_eq!(hexchess.current_moves(), vec![s!("N@f10")]);
+
+            hexchess.pockets = [[0; 5]; 2];
+            assert!(hexchess.is_checkmate());
         }
 
         #[test]
-        fn fen_with_skip_7() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/7p3/11/11/11/11/11 w - 0 1").unwrap();
+        fn antichess_forced_captures() {
+            let mut hexchess = Hexchess::init_variant(Variant::Antichess);
+            assert_eq!(hexchess.current_moves().len(), 51);
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("h6")]);
+            hexchess.apply("g4g6").unwrap();
+            assert_eq!(hexchess.current_moves(), vec![s!("f7g6")]);
+            assert!(!hexchess.is_legal(&s!("e7e6")));
+
+            // the same position has other moves in glinski
+            hexchess.variant = Variant::Glinski;
+            assert!(hexchess.is_legal(&s!("e7e6")));
         }
 
         #[test]
-        fn fen_with_skip_8() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/8p2/11/11/11/11/11 w - 0 1").unwrap();
+        fn antichess_kings() {
+            // kings may be captured, left in check, and appear more than once
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/5k5/4K6 b - 0 1", Variant::Antichess).unwrap();
+            assert_eq!(hexchess.validate(), Ok(()));
+            assert!(!hexchess.is_check());
+            assert_eq!(hexchess.current_moves(), vec![s!("f2e1")]);
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("i6")]);
+            hexchess.apply("f2e1").unwrap();
+            assert!(hexchess.get_color(Color::White).is_empty());
+            assert!(hexchess.is_stalemate());
+
+            assert!(Hexchess::parse("k/3/5/7/9/11/11/11/11/5k5/4K6 b - 0 1").is_err());
         }
 
         #[test]
-        fn fen_with_skip_9() {
-        let hexchess = Hexchess::parse("1/3/5/7/9/9p1/11/11/11/11/11 w - 0 1").unwrap();
+        fn antichess_king_promotion() {
+            let hexchess = Hexchess::parse_variant("1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1", Variant::Antichess).unwrap();
+            let promotion = Variant::Antichess.parse_san("f2f1k").unwrap();
+            assert!(hexchess.current_moves().contains(&promotion));
 
-        assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("k6")]);
+            let mut hexchess = hexchess;
+            hexchess.apply("f2f1k").unwrap();
+            assert_eq!(hexchess.get("f1"), Some(Piece::BlackKing));
+
+            // kings are never promoted to when they are royal
+            let mut glinski = Hexchess::parse("1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1").unwrap();
+            assert!(!glinski.moves_from(h!("f2")).contains(&promotion));
+            assert_eq!(glinski.apply("f2f1k"), Err("invalid san at index 0: f2f1k".to_string()));
+            assert_eq!(glinski.try_apply_move_unsafe(&promotion).err(), Some("cannot promote to king in variant: glinski".to_string()));
         }
 
         #[test]
-        fn fen_with_skip_10() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/p10/11/11/11/11/11 w - 0 1").unwrap();
+        fn dark_king_capture() {
+            // without check, the king may move next to the queen
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/5q5/5K5 w - 0 1", Variant::Dark).unwrap();
+            assert_eq!(hexchess.current_moves().len(), 5);
+            assert_eq!(hexchess.validate(), Ok(()));
 
-            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("a6")]);
+            hexchess.apply("f1e1 f2e1").unwrap();
+            assert_eq!(hexchess.variant_winner(), Some(Color::Black));
+            assert_eq!(hexchess.validate(), Ok(()));
+
+            // kings are still never promoted to
+            let hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/5p5/5K5 b - 0 1", Variant::Dark).unwrap();
+            assert!(!hexchess.current_moves().iter().any(|san| san.promotion == Some(PromotionPiece::King)));
         }
 
         #[test]
-        fn fen_with_skip_11() {
-            let hexchess = Hexchess::parse("1/3/5/7/9/11/p10/11/11/11/11 w - 0 1").unwrap();
+        fn king_of_the_hill() {
+            let mut hexchess = Hexchess::parse_variant("1/1k1/5/7/9/11/11/5K5/11/11/11 w - 0 1", Variant::KingOfTheHill).unwrap();
+            assert_eq!(hexchess.variant_winner(), None);
 
-            assert_eq!(Some(Piece::BlackPawn), hexchess.board[h!("a5")]);
+            hexchess.apply("f4f5").unwrap();
+            assert_eq!(hexchess.variant_winner(), Some(Color::White));
+
+            // the hill means nothing in other variants
+            hexchess.variant = Variant::Glinski;
+            assert_eq!(hexchess.variant_winner(), None);
         }
 
-    }
+        #[test]
+        fn three_check() {
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/R4K5 w - 0+0 0 1", Variant::ThreeCheck).unwrap();
 
-    mod self_check {
-        use super::*;
+            hexchess.apply("a1f6").unwrap();
+            assert_eq!(hexchess.checks, [1, 0]);
+            assert_eq!(hexchess.to_string(), "k/3/5/7/9/5R5/11/11/11/11/5K5 b - 1+0 1 1");
+            assert_eq!(hexchess.variant_winner(), None);
 
-        #[test]
-        fn cannot_step_out_of_a_pin() {
-            let hexchess = Hexchess::parse("1/3/5/7/4K4/5R5/5q5/11/11/11/11 w - 0 1").unwrap();
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/R4K5 w - 2+0 0 1", Variant::ThreeCheck).unwrap();
 
-            let moves = hexchess.moves_from(h!("f6"));
-            assert_eq!(moves.len(), 1);
-            assert_eq!(moves[0], s!("f6f5"));
-        }
+            hexchess.apply("a1a6").unwrap();
+            assert_eq!(hexchess.checks, [3, 0]);
+            assert_eq!(hexchess.variant_winner(), Some(Color::White));
+            assert_eq!(hexchess.flip_colors().variant_winner(), Some(Color::Black));
 
-        // cannot self check on opponent's turn
-        #[test]
-        fn cannot_self_check_on_opponents_turn() {
-            let hexchess    = Hexchess::parse("1/3/5/7/4K4/5R5/5q5/11/11/11/11 b - 0 1").unwrap();
-            let moves = hexchess.moves_from(h!("f6"));
+            // only legal moves count checks, unsafe moves are left cheap
+            let mut hexchess = Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/R4K5 w - 0+0 0 1", Variant::ThreeCheck).unwrap();
 
-            assert_eq!(moves.len(), 1);
-            assert_eq!(moves[0], s!("f6f5"));
+            hexchess.apply_move_unsafe(&s!("a1f6"));
+            assert_eq!(hexchess.checks, [0, 0]);
         }
 
-        // king cannot step into check
         #[test]
-        fn king_cannot_step_into_check() {
-            let hexchess = Hexchess::parse("K/3/2q2/7/9/11/11/11/11/11/11 w - 0 1").unwrap();
-            let moves = hexchess.moves_from(h!("f11"));
+        fn three_check_fen() {
+            let hexchess = Hexchess::init_variant(Variant::ThreeCheck);
+            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0+0 0 1");
 
-            assert_eq!(moves.len(), 0);
-        }
-    }
+            // the checks field may be omitted
+            assert_eq!(Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/5K5 w - 0 1", Variant::ThreeCheck).unwrap().checks, [0; 2]);
 
-    #[test]
-    fn test_to_piece() {
-        assert_eq!(to_piece('b'), Ok(Piece::BlackBishop));
-        assert_eq!(to_piece('B'), Ok(Piece::WhiteBishop));
-        assert_eq!(to_piece('k'), Ok(Piece::BlackKing)); // <- not called during normal board parsing
-        assert_eq!(to_piece('K'), Ok(Piece::WhiteKing)); // <- not called during normal board parsing
-        assert_eq!(to_piece('n'), Ok(Piece::BlackKnight));
-        assert_eq!(to_piece('N'), Ok(Piece::WhiteKnight));
-        assert_eq!(to_piece('p'), Ok(Piece::BlackPawn));
-        assert_eq!(to_piece('P'), Ok(Piece::WhitePawn));
-        assert_eq!(to_piece('q'), Ok(Piece::BlackQueen));
-        assert_eq!(to_piece('Q'), Ok(Piece::WhiteQueen));
-        assert_eq!(to_piece('r'), Ok(Piece::BlackRook));
-        assert_eq!(to_piece('R'), Ok(Piece::WhiteRook));
-    }
+            assert_eq!(
+                Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/5K5 w - 4+0 0 1", Variant::ThreeCheck),
+                Err("invalid checks: 4+0".to_string()),
+            );
 
-    #[test]
-    fn test_to_piece_invalid() {
-        assert_eq!(to_piece('x'), Err("invalid_piece_character"));
-        assert_eq!(to_piece('1'), Err("invalid_piece_character"));
-        assert_eq!(to_piece('/'), Err("invalid_piece_character"));
-        assert_eq!(to_piece(' '), Err("invalid_piece_character"));
+            assert_eq!(
+                Hexchess::parse_variant("k/3/5/7/9/11/11/11/11/11/5K5 w - 1+x 0 1", Variant::ThreeCheck),
+                Err("invalid checks: 1+x".to_string()),
+            );
+        }
     }
 
-    mod to_string {
+    mod visible_cells {
         use super::*;
 
         #[test]
-        fn empty_position() {
-            let hexchess = Hexchess::new();
+        fn occupied_and_reachable_cells() {
+            let hexchess = Hexchess::init();
+            let visible = hexchess.visible_cells(Color::White);
 
-            assert_eq!(hexchess.to_string(), "1/3/5/7/9/11/11/11/11/11/11 w - 0 1");
+            let is_visible = |position: u8| visible[position as usize];
+
+            assert_eq!(visible.iter().filter(|visible| **visible).count(), 48);
+            assert!(is_visible(h!("g1")));
+            assert!(is_visible(h!("g6"))); // <- pawn double step
+            assert!(!is_visible(h!("g7")));
+            assert!(!is_visible(h!("g10")));
         }
 
         #[test]
-        fn initial_position() {
-            let hexchess = Hexchess::init();
+        fn redacted_view() {
+            assert_eq!(
+                Hexchess::init().redacted_view(Color::White).to_string(),
+                "1/3/5/7/9/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 1",
+            );
 
-            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 1");
+            // the pawn that can be captured en passant is visible, and so is it's target
+            let mut hexchess = Hexchess::init_variant(Variant::Dark);
+            hexchess.apply("g4g6 f7g6 f5f7").unwrap();
+
+            assert_eq!(
+                hexchess.redacted_view(Color::Black).to_string(),
+                "b/qbk/n1b1n/r5r/ppppPpppp/6p4/11/11/11/11/11 b f6 0 2",
+            );
         }
 
         #[test]
-        fn with_en_passant() {
-            let mut hexchess = Hexchess::init();
-
-            let _ = hexchess.apply_move(&s!("g4g6"));
+        fn redacted_castling_rights() {
+            let hexchess = Hexchess::init_variant(Variant::Shafran);
 
-            assert_eq!(hexchess.to_string(), "b/qbk/n1b1n/r5r/ppppppppp/6P4/5P5/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 b g5 0 1");
+            assert_eq!(hexchess.redacted_view(Color::White).castling, [true, true, false, false]);
+            assert_eq!(hexchess.redacted_view(Color::Black).castling, [false, false, true, true]);
         }
     }
 }
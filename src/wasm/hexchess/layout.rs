@@ -0,0 +1,260 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify_next::Tsify;
+
+use crate::hexchess::geometry::Cell;
+
+const SQRT_3: f64 = 1.732_050_807_568_877_2;
+
+/// Orientation of cells on screen
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Orientation {
+    /// Flat-topped cells, files run vertically with white at the bottom
+    #[default]
+    Flat,
+
+    /// Pointy-topped cells, the board is turned a quarter clockwise with white on the left
+    Pointy,
+}
+
+/// Pixel coordinate, with y increasing down the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Point {
+    pub x: f64,
+
+    pub y: f64,
+}
+
+/// Pixel layout of the hexboard, with the origin at the top left of it's bounding box
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Layout {
+    /// Rotate the board half a turn, putting black nearest the viewer
+    pub flipped: bool,
+
+    /// Orientation of cells
+    pub orientation: Orientation,
+
+    /// Distance from the center of a cell to it's corners
+    pub size: f64,
+}
+
+impl Layout {
+    pub fn new(size: f64, orientation: Orientation, flipped: bool) -> Self {
+        Self {
+            flipped,
+            orientation,
+            size,
+        }
+    }
+
+    /// get the cell under a pixel coordinate
+    pub fn cell_at(&self, point: Point) -> Option<Cell> {
+        let cx = point.x - self.width() / 2.0;
+        let cy = point.y - self.height() / 2.0;
+
+        let (fx, fy) = match self.orientation {
+            Orientation::Flat => (cx, cy),
+            Orientation::Pointy => (cy, -cx),
+        };
+
+        let q = (2.0 / 3.0 * fx) / self.size;
+        let r = (-1.0 / 3.0 * fx + SQRT_3 / 3.0 * fy) / self.size;
+        let (q, r) = cube_round(q, r);
+
+        match self.flipped {
+            true => Cell::from_axial(-q, -r),
+            false => Cell::from_axial(q, r),
+        }
+    }
+
+    /// get the pixel center of a cell
+    pub fn center(&self, cell: Cell) -> Point {
+        let (q, r) = match self.flipped {
+            true => {
+                let (q, r) = cell.axial();
+                (-q, -r)
+            },
+            false => cell.axial(),
+        };
+
+        let fx = self.size * 1.5 * q as f64;
+        let fy = self.size * SQRT_3 * (r as f64 + q as f64 / 2.0);
+
+        let (cx, cy) = match self.orientation {
+            Orientation::Flat => (fx, fy),
+            Orientation::Pointy => (-fy, fx),
+        };
+
+        Point {
+            x: cx + self.width() / 2.0,
+            y: cy + self.height() / 2.0,
+        }
+    }
+
+    /// get the corner points of a cell, clockwise
+    pub fn corners(&self, cell: Cell) -> Vec<Point> {
+        let center = self.center(cell);
+
+        let offset = match self.orientation {
+            Orientation::Flat => 0.0,
+            Orientation::Pointy => 30.0,
+        };
+
+        (0..6)
+            .map(|n| {
+                let angle = (offset + 60.0 * n as f64).to_radians();
+
+                Point {
+                    x: center.x + self.size * angle.cos(),
+                    y: center.y + self.size * angle.sin(),
+                }
+            })
+            .collect()
+    }
+
+    /// get the height of the board
+    pub fn height(&self) -> f64 {
+        match self.orientation {
+            Orientation::Flat => self.size * SQRT_3 * 11.0,
+            Orientation::Pointy => self.size * 17.0,
+        }
+    }
+
+    /// get the width of the board
+    pub fn width(&self) -> f64 {
+        match self.orientation {
+            Orientation::Flat => self.size * 17.0,
+            Orientation::Pointy => self.size * SQRT_3 * 11.0,
+        }
+    }
+}
+
+/// round fractional axial coordinates to the nearest cell
+fn cube_round(q: f64, r: f64) -> (i8, i8) {
+    let s = -q - r;
+
+    let mut rq = q.round();
+    let mut rr = r.round();
+    let rs = s.round();
+
+    let dq = (rq - q).abs();
+    let dr = (rr - r).abs();
+    let ds = (rs - s).abs();
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    (rq as i8, rr as i8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(position: &str) -> Cell {
+        Cell::from_position(position).unwrap()
+    }
+
+    fn layouts() -> [Layout; 4] {
+        [
+            Layout::new(10.0, Orientation::Flat, false),
+            Layout::new(10.0, Orientation::Flat, true),
+            Layout::new(10.0, Orientation::Pointy, false),
+            Layout::new(10.0, Orientation::Pointy, true),
+        ]
+    }
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn center_of_board() {
+        for layout in layouts() {
+            let center = layout.center(cell("f6"));
+
+            assert!(near(center.x, layout.width() / 2.0));
+            assert!(near(center.y, layout.height() / 2.0));
+        }
+    }
+
+    #[test]
+    fn flat_board_edges() {
+        let layout = Layout::new(10.0, Orientation::Flat, false);
+
+        let f11 = layout.center(cell("f11"));
+        assert!(near(f11.x, 85.0));
+        assert!(near(f11.y, 5.0 * SQRT_3));
+
+        let a1 = layout.center(cell("a1"));
+        assert!(near(a1.x, 10.0));
+
+        let f1 = layout.center(cell("f1"));
+        assert!(near(f1.y, layout.height() - 5.0 * SQRT_3));
+    }
+
+    #[test]
+    fn flipped_board() {
+        let white = Layout::new(10.0, Orientation::Flat, false);
+        let black = Layout::new(10.0, Orientation::Flat, true);
+
+        assert_eq!(black.center(cell("f1")), white.center(cell("f11")));
+        assert_eq!(black.center(cell("a1")), white.center(cell("l6")));
+    }
+
+    #[test]
+    fn pointy_board() {
+        let layout = Layout::new(10.0, Orientation::Pointy, false);
+
+        let f1 = layout.center(cell("f1"));
+        let f11 = layout.center(cell("f11"));
+
+        assert!(f1.x < f11.x);
+        assert!(near(f1.y, f11.y));
+    }
+
+    #[test]
+    fn corners() {
+        for layout in layouts() {
+            let center = layout.center(cell("c4"));
+            let corners = layout.corners(cell("c4"));
+
+            assert_eq!(corners.len(), 6);
+
+            for corner in corners {
+                assert!(near((corner.x - center.x).hypot(corner.y - center.y), 10.0));
+            }
+        }
+    }
+
+    #[test]
+    fn cell_at_round_trip() {
+        for layout in layouts() {
+            for c in Cell::all() {
+                let center = layout.center(c);
+
+                assert_eq!(layout.cell_at(center), Some(c));
+                assert_eq!(layout.cell_at(Point { x: center.x + 4.0, y: center.y - 4.0 }), Some(c));
+            }
+        }
+    }
+
+    #[test]
+    fn cell_at_outside_board() {
+        for layout in layouts() {
+            assert_eq!(layout.cell_at(Point { x: 0.0, y: 0.0 }), None);
+            assert_eq!(layout.cell_at(Point { x: layout.width(), y: layout.height() }), None);
+        }
+    }
+}
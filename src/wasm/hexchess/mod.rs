@@ -1,5 +1,14 @@
 mod pieces;
+pub mod board;
+pub mod codec;
+pub mod game;
+pub mod geometry;
 pub mod hexchess;
+pub mod layout;
 pub mod san;
+pub mod shuffle;
+pub mod svg;
+pub mod text;
 pub mod utils;
 
+pub mod variant;
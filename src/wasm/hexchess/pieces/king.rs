@@ -1,12 +1,15 @@
 use crate::constants::Color;
 use crate::hexchess::hexchess::Hexchess;
 use crate::hexchess::san::San;
+use crate::hexchess::utils::get_color;
+use crate::hexchess::variant::BoardGeometry;
 
-use crate::hexchess::utils::{
-    get_color,
-    step,
-};
-
+/// get positions attacked by a king, including friendly pieces
+pub fn king_attacks(geometry: &BoardGeometry, from: u8) -> Vec<u8> {
+    (0u8..12u8)
+        .filter_map(|n| geometry.step(from, n))
+        .collect()
+}
 
 pub fn king_moves_unsafe(
     hexchess: &Hexchess,
@@ -18,7 +21,7 @@ pub fn king_moves_unsafe(
     let directions: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
 
     for n in directions {
-        let to = match step(from, n) {
+        let to = match hexchess.geometry().step(from, n) {
             Some(to) => to,
             None => continue,
         };
@@ -38,11 +41,69 @@ pub fn king_moves_unsafe(
     result
 }
 
+/// get castling moves of a king, written as the king moving onto it's rook
+pub fn castling_moves(
+    hexchess: &Hexchess,
+    from: u8,
+    color: &Color,
+) -> Vec<San> {
+    let mut result: Vec<San> = vec![];
+
+    let geometry = hexchess.geometry();
+
+    let opponent = match color {
+        Color::Black => Color::White,
+        Color::White => Color::Black,
+    };
+
+    let mut attacked: Option<[u8; 91]> = None;
+
+    for (i, castling) in geometry.castling.iter().enumerate() {
+        let castling = match castling {
+            Some(castling) if castling.color == *color && castling.king == from && hexchess.castling[i] => castling,
+            _ => continue,
+        };
+
+        let rook = match hexchess.board[castling.rook as usize] {
+            Some(piece) => piece,
+            None => continue,
+        };
+
+        if get_color(&rook) != *color || castling.path.iter().any(|n| hexchess.board[*n as usize].is_some()) {
+            continue;
+        }
+
+        // the king may not castle out of, or through, check
+        let attacked = attacked.get_or_insert_with(|| hexchess.attack_map(opponent));
+        let crossed = castling.path.iter().position(|n| *n == castling.king_to).unwrap_or(0);
+
+        if attacked[from as usize] > 0 || castling.path[..=crossed].iter().any(|n| attacked[*n as usize] > 0) {
+            continue;
+        }
+
+        result.push(San { from, promotion: None, to: castling.rook });
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use crate::h;
+    use crate::hexchess::variant::Variant;
     use super::*;
 
+    #[test]
+    fn king_attacks_near_edge_of_board() {
+        assert_eq!(king_attacks(Variant::Glinski.geometry(), h!("f11")), vec![
+            h!("g10"),
+            h!("g9"),
+            h!("f10"),
+            h!("e9"),
+            h!("e10"),
+        ]);
+    }
+
     #[test]
     fn white_king() {
         let result = Hexchess::parse("1/3/5/7/3P5/5K5/11/6p4/11/11/11 w - 0 1")
@@ -1,11 +1,39 @@
 use crate::constants::Color;
 use crate::hexchess::hexchess::Hexchess;
 use crate::hexchess::san::San;
-
 use crate::hexchess::utils::{
     get_color,
     step,
 };
+use crate::hexchess::variant::BoardGeometry;
+
+/// diagonal direction, first orthogonal direction, second orthogonal direction
+const KNIGHT_TARGETS: [(u8, u8, u8); 6] = [
+    (1, 0, 2),
+    (3, 2, 4),
+    (5, 4, 6),
+    (7, 6, 8),
+    (9, 8, 10),
+    (11, 10, 0),
+];
+
+/// get positions attacked by a knight, including friendly pieces
+pub fn knight_attacks(geometry: &BoardGeometry, from: u8) -> Vec<u8> {
+    let mut result: Vec<u8> = vec![];
+
+    for (diagonal, orthogonal1, orthogonal2) in KNIGHT_TARGETS {
+        // knights leap, so the intermediate cell may be off the board
+        let intermediate = match step(from, diagonal) {
+            Some(index) => index,
+            None => continue,
+        };
+
+        result.extend(geometry.step(intermediate, orthogonal1));
+        result.extend(geometry.step(intermediate, orthogonal2));
+    }
+
+    result
+}
 
 pub fn knight_moves_unsafe(
     hexchess: &Hexchess,
@@ -14,17 +42,7 @@ pub fn knight_moves_unsafe(
 ) -> Vec<San> {
     let mut result: Vec<San> = vec![];
 
-    // diagonal direction, first orthogonal direction, second orthogonal direction
-    let targets: [(u8, u8, u8); 6] = [
-        (1, 0, 2),
-        (3, 2, 4),
-        (5, 4, 6),
-        (7, 6, 8),
-        (9, 8, 10),
-        (11, 10, 0),
-    ];
-
-    for (diagonal, orthogonal1, orthagonal2) in targets {
+    for (diagonal, orthogonal1, orthagonal2) in KNIGHT_TARGETS {
         let intermediate = match step(from, diagonal) {
             Some(index) => index,
             None => continue,
@@ -45,7 +63,7 @@ pub fn knight_moves_unsafe(
 }
 
 fn knight_steps(hexchess: &Hexchess, from: u8, intermediate: u8, orthogonal: u8, color: &Color) -> Option<San> {
-    match step(intermediate, orthogonal) {
+    match hexchess.geometry().step(intermediate, orthogonal) {
         Some(to) => match hexchess.board[to as usize] {
             Some(piece) => match get_color(&piece) != *color {
                 true => Some(San { from, promotion: None, to }),
@@ -60,8 +78,19 @@ fn knight_steps(hexchess: &Hexchess, from: u8, intermediate: u8, orthogonal: u8,
 #[cfg(test)]
 mod tests {
     use crate::{h, s};
+    use crate::hexchess::variant::Variant;
     use super::*;
 
+    #[test]
+    fn knight_attacks_include_friendly_pieces() {
+        let hexchess = Hexchess::parse("1/3/5/2P1p2/9/5N5/11/11/11/11/11 w - 0 1").unwrap();
+        let attacks = knight_attacks(Variant::Glinski.geometry(), h!("f6"));
+
+        assert_eq!(attacks.len(), 12);
+        assert_eq!(attacks.len(), hexchess.moves_from(h!("f6")).len() + 1);
+        assert!(attacks.contains(&h!("e8"))); // <- e8 is friendly
+    }
+
     #[test]
     fn white_knight() {
         let result = Hexchess::parse("1/3/5/2P1p2/9/5N5/11/11/11/11/11 w - 0 1")
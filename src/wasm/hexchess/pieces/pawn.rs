@@ -1,17 +1,27 @@
-use crate::h;
+use crate::hexchess::geometry::Direction;
 use crate::hexchess::hexchess::Hexchess;
 use crate::hexchess::san::San;
+use crate::hexchess::variant::BoardGeometry;
 
 use crate::constants::{
     Color,
     PromotionPiece,
 };
 
-use crate::hexchess::utils::{
-    get_color,
-    step,
-};
+use crate::hexchess::utils::get_color;
+
+
+/// get positions attacked by a pawn, regardless of occupancy
+pub fn pawn_attacks(geometry: &BoardGeometry, from: u8, color: &Color) -> Vec<u8> {
+    let [portside_direction, starboard_direction] = geometry.pawn_captures(*color);
+
+    let mut result: Vec<u8> = vec![];
 
+    result.extend(geometry.step(from, portside_direction));
+    result.extend(geometry.step(from, starboard_direction));
+
+    result
+}
 
 pub fn pawn_moves_unsafe(
     hexchess: &Hexchess,
@@ -20,23 +30,18 @@ pub fn pawn_moves_unsafe(
 ) -> Vec<San> {
     let mut result: Vec<San> = vec![];
 
-    let (
-      forward_direction, 
-      portside_direction, 
-      starboard_direction
-    ) = match color {
-      Color::White => (0u8, 10u8, 2u8),
-      Color::Black => (6u8, 4u8, 8u8),
-    };
+    let geometry = hexchess.geometry();
+    let forward_direction = geometry.pawn_advance(*color);
+    let [portside_direction, starboard_direction] = geometry.pawn_captures(*color);
 
     // advance forward one position
     match advance(hexchess, from, from, forward_direction) {
         None => {},
         Some(san) => {
-            push_moves(&mut result, san, *color);
+            push_moves(hexchess, &mut result, san, *color);
 
             // advance forward another position if possible
-            if is_starting_position(from, *color) {
+            if is_starting_position(geometry, from, *color) {
                 match advance(hexchess, from, san.to, forward_direction) {
                     None => {}
                     Some(san) => result.push(san),
@@ -48,22 +53,22 @@ pub fn pawn_moves_unsafe(
     // capture portside
     match capture(hexchess, from, portside_direction, *color) {
         None => {},
-        Some(san) => push_moves(&mut result, san, *color),
+        Some(san) => push_moves(hexchess, &mut result, san, *color),
     };
 
     // capture starboard
     match capture(hexchess, from, starboard_direction, *color) {
         None => {},
-        Some(san) => push_moves(&mut result, san, *color),
+        Some(san) => push_moves(hexchess, &mut result, san, *color),
     };
         
     result
 }
 
-fn advance(hexchess: &Hexchess, start: u8, from: u8, forward_direction: u8) -> Option<San> {
+fn advance(hexchess: &Hexchess, start: u8, from: u8, forward_direction: Direction) -> Option<San> {
     // we don't need to verify the step exists, because pawns cannot exist
     // on the final rank without promoting. there will always be one more step.
-    let to = step(from, forward_direction).unwrap();
+    let to = hexchess.geometry().step(from, forward_direction).unwrap();
 
     match hexchess.board[to as usize] {
         None => Some(San { from: start, promotion: None, to }),
@@ -71,8 +76,8 @@ fn advance(hexchess: &Hexchess, start: u8, from: u8, forward_direction: u8) -> O
     }
 }
 
-fn capture(hexchess: &Hexchess, from: u8, capture_direction: u8, friendly_color: Color) -> Option<San> {
-    match step(from, capture_direction) {
+fn capture(hexchess: &Hexchess, from: u8, capture_direction: Direction, friendly_color: Color) -> Option<San> {
+    match hexchess.geometry().step(from, capture_direction) {
         None => None,
         Some(to) => match hexchess.board[to as usize] {
             None => match hexchess.ep {
@@ -90,78 +95,26 @@ fn capture(hexchess: &Hexchess, from: u8, capture_direction: u8, friendly_color:
     }
 }
 
-fn is_promotion_position(position: u8, color: Color) -> bool {
-    match color {
-        Color::Black => match position {
-            h!("a1") |
-            h!("b1") |
-            h!("c1") |
-            h!("d1") |
-            h!("e1") |
-            h!("f1") |
-            h!("g1") |
-            h!("h1") |
-            h!("i1") |
-            h!("k1") |
-            h!("l1") => true,
-            _ => false,
-        },
-        Color::White => match position {
-            h!("a6") |
-            h!("b7") |
-            h!("c8") |
-            h!("d9") |
-            h!("e10") |
-            h!("f11") |
-            h!("g10") |
-            h!("h9") |
-            h!("i8") |
-            h!("k7") |
-            h!("l6") => true,
-            _ => false,
-        }
-    }
-}
-
-fn is_starting_position(position: u8, color: Color) -> bool {
-    match color {
-        Color::Black => match position {
-            h!("b7") |
-            h!("c7") |
-            h!("d7") |
-            h!("e7") |
-            h!("f7") |
-            h!("g7") |
-            h!("h7") |
-            h!("i7") |
-            h!("k7") => true,
-            _ => false,
-        },
-        Color::White => match position {
-            h!("b1") |
-            h!("c2") |
-            h!("d3") |
-            h!("e4") |
-            h!("f5") |
-            h!("g4") |
-            h!("h3") |
-            h!("i2") |
-            h!("k1") => true,
-            _ => false,
-        }
-    }
+/// test if position is a pawn's starting position
+pub fn is_starting_position(geometry: &BoardGeometry, position: u8, color: Color) -> bool {
+    geometry.is_double_step(position, color)
 }
 
 fn push_moves(
+    hexchess: &Hexchess,
     result: &mut Vec<San>,
     san: San,
     color: Color,
 ) {
-    if is_promotion_position(san.to, color) {
+    if hexchess.geometry().is_promotion(san.to, color) {
         result.push(San { from: san.from, promotion: Some(PromotionPiece::Bishop), to: san.to });
         result.push(San { from: san.from, promotion: Some(PromotionPiece::Knight), to: san.to });
         result.push(San { from: san.from, promotion: Some(PromotionPiece::Queen), to: san.to });
         result.push(San { from: san.from, promotion: Some(PromotionPiece::Rook), to: san.to });
+
+        if hexchess.variant.has_king_promotion() {
+            result.push(San { from: san.from, promotion: Some(PromotionPiece::King), to: san.to });
+        }
     } else {
         result.push(san);
     }
@@ -170,8 +123,21 @@ fn push_moves(
 #[cfg(test)]
 mod tests {
     use crate::{h, s};
+    use crate::hexchess::variant::Variant;
     use super::*;
 
+    #[test]
+    fn pawn_attacks_empty_positions() {
+        assert_eq!(pawn_attacks(Variant::Glinski.geometry(), h!("f5"), &Color::White), vec![h!("e5"), h!("g5")]);
+        assert_eq!(pawn_attacks(Variant::Glinski.geometry(), h!("f7"), &Color::Black), vec![h!("g6"), h!("e6")]);
+    }
+
+    #[test]
+    fn pawn_attacks_near_edge_of_board() {
+        assert_eq!(pawn_attacks(Variant::Glinski.geometry(), h!("a4"), &Color::White), vec![h!("b5")]);
+        assert_eq!(pawn_attacks(Variant::Glinski.geometry(), h!("l4"), &Color::Black), vec![h!("k4")]);
+    }
+
     #[test]
     fn black_starting_pawns() {
         let b7 = Hexchess::parse("1/3/5/7/ppppppppp/11/11/11/11/11/11 b - 0 1").unwrap().moves_from(h!("b7"));
@@ -1,18 +1,44 @@
 use crate::constants::Color;
+use crate::hexchess::geometry::Direction;
 use crate::hexchess::hexchess::Hexchess;
 use crate::hexchess::san::San;
 use crate::hexchess::utils::walk;
 
+/// get positions attacked by a slider, up to and including the first piece
+pub fn straight_line_attacks(
+    hexchess: &Hexchess,
+    from: &u8,
+    directions: &[Direction],
+) -> Vec<u8> {
+    let mut result: Vec<u8> = vec![];
+
+    for n in directions {
+        let mut position = *from;
+
+        while let Some(to) = hexchess.geometry().step(position, *n) {
+            result.push(to);
+
+            if hexchess.board[to as usize].is_some() {
+                break;
+            }
+
+            position = to;
+        }
+    }
+
+    result
+}
+
 pub fn straight_line_moves_unsafe(
     hexchess: &Hexchess,
     from: &u8,
     color: &Color,
-    directions: &[u8],
+    directions: &[Direction],
 ) -> Vec<San> {
     let mut result: Vec<San> = vec![];
 
     for n in directions {
-        let path = walk(hexchess, *from, *n, color);
+        let path = walk(hexchess, *from, n.index(), color);
 
         for to in path {
             result.push(San {
@@ -31,6 +57,20 @@ mod tests {
     use crate::h;
     use super::*;
 
+    #[test]
+    fn straight_line_attacks_stop_on_any_piece() {
+        let hexchess = Hexchess::parse("1/3/2P2/7/9/2p2R5/11/11/11/11/11 w - 0 1").unwrap();
+
+        assert_eq!(straight_line_attacks(&hexchess, &h!("f6"), &[Direction::North, Direction::NorthWest]), vec![
+            h!("f7"),
+            h!("f8"),
+            h!("f9"), // <- f9 is friendly
+            h!("e6"),
+            h!("d6"),
+            h!("c6"), // <- c6 is hostile
+        ]);
+    }
+
     #[test]
     fn white_queen() {
         let result = Hexchess::parse("p/3/5/7/9/P4Q5/11/11/11/11/11 w - 0 1")
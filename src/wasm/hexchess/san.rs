@@ -1,24 +1,42 @@
-use crate::constants::PromotionPiece;
+use crate::constants::{Piece, PromotionPiece};
+use crate::hexchess::variant::BoardGeometry;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
 use tsify_next::Tsify;
 
 use crate::hexchess::utils::{
     is_promotion_position,
     index,
+    swap_color,
 };
 
 use super::utils::to_position;
 
+/// Pieces that may be dropped from hand, in the order of their drop index
+pub const DROP_PIECES: [Piece; 5] = [
+    Piece::WhitePawn,
+    Piece::WhiteKnight,
+    Piece::WhiteBishop,
+    Piece::WhiteRook,
+    Piece::WhiteQueen,
+];
+
+/// Added to a piece's drop index to form the `from` of a drop move
+pub const DROP_OFFSET: u8 = 91;
+
 /// Struct representing a single move.
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi, type_suffix = "Struct")]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi, type_suffix = "Struct"))]
 pub struct San {
-    /// From position index, 0..91
+    /// From position index, 0..91, or 91 plus the drop index of a piece dropped from hand
     pub from: u8,
 
     /// Promotion piece
-    #[tsify(type = "PromotionPiece | null")]
+    #[cfg_attr(feature = "wasm", tsify(type = "VariantPromotionPiece | null"))]
     pub promotion: Option<PromotionPiece>,
 
     /// Target position index, 0..91
@@ -26,7 +44,48 @@ pub struct San {
 }
 
 impl San {
+    /// create a move dropping a piece from hand, either color may be given
+    pub fn new_drop(piece: Piece, to: u8) -> Option<Self> {
+        let from = DROP_OFFSET + drop_index(piece)? as u8;
+
+        Some(Self { from, promotion: None, to })
+    }
+
+    /// get the piece dropped by the move, as a white piece
+    pub fn dropped(&self) -> Option<Piece> {
+        DROP_PIECES.get(self.from.checked_sub(DROP_OFFSET)? as usize).copied()
+    }
+
+    /// turn the move half way around a board, as played by the other color
+    pub fn flip_colors(&self, geometry: &BoardGeometry) -> Self {
+        Self {
+            from: transform(self.from, |position| geometry.rotate(position)),
+            promotion: self.promotion,
+            to: transform(self.to, |position| geometry.rotate(position)),
+        }
+    }
+
+    /// reflect the move across the f-file of a board
+    pub fn mirror(&self, geometry: &BoardGeometry) -> Self {
+        Self {
+            from: transform(self.from, |position| geometry.mirror(position)),
+            promotion: self.promotion,
+            to: transform(self.to, |position| geometry.mirror(position)),
+        }
+    }
+
+    /// parse san from a string
+    #[deprecated(note = "use `San::parse` or `str::parse` instead")]
     pub fn from(source: &str) -> Result<Self, String> {
+        Self::parse(source)
+    }
+
+    /// parse san from a string
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if let Some((piece, to_source)) = source.split_once('@') {
+            return parse_drop(piece, to_source, |name| index(name).ok());
+        }
+
         let mut chars = source.chars();
 
         // first file
@@ -150,24 +209,62 @@ impl San {
 
 impl fmt::Display for San {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(piece) = self.dropped() {
+            return write!(f, "{}@{}", piece, to_position(&self.to));
+        }
+
         let mut value = to_position(&self.from).to_string() + &to_position(&self.to).to_string();
 
-        match self.promotion {
-            Some(promotion) => {
-                value.push(match promotion {
-                    PromotionPiece::Bishop => 'b',
-                    PromotionPiece::Knight => 'n',
-                    PromotionPiece::Queen => 'q',
-                    PromotionPiece::Rook => 'r',
-                });
-            }
-            None => {}
-        };
+        if let Some(promotion) = self.promotion {
+            value.push_str(&promotion.to_string());
+        }
 
         write!(f, "{}", value)
     }
 }
 
+impl FromStr for San {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+impl TryFrom<&str> for San {
+    type Error = String;
+
+    fn try_from(source: &str) -> Result<Self, Self::Error> {
+        Self::parse(source)
+    }
+}
+
+/// get the drop index of a piece of either color, kings can't be dropped
+pub fn drop_index(piece: Piece) -> Option<usize> {
+    DROP_PIECES.iter().position(|p| *p == piece || *p == swap_color(&piece))
+}
+
+/// parse a drop move, a piece letter and target position separated by `@`
+pub fn parse_drop(piece: &str, to_source: &str, position_index: impl Fn(&str) -> Option<u8>) -> Result<San, String> {
+    let piece = match piece.parse::<Piece>().ok().filter(|p| DROP_PIECES.contains(p)) {
+        Some(piece) => piece,
+        None => return Err(format!("invalid drop piece: {}", piece)),
+    };
+
+    match position_index(to_source) {
+        Some(to) => Ok(San::new_drop(piece, to).unwrap()),
+        None => Err(format!("invalid to position: {}", to_source)),
+    }
+}
+
+/// transform a position index, leaving drops in place
+fn transform(position: u8, f: impl Fn(u8) -> u8) -> u8 {
+    match position < DROP_OFFSET {
+        true => f(position),
+        false => position,
+    }
+}
+
 /// test if character is a file
 fn is_file(c: char) -> bool {
     match c {
@@ -185,8 +282,10 @@ fn is_rank(c: char) -> bool {
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use crate::{h, s};
+    use crate::hexchess::variant::Variant;
     use super::*;
 
     #[test]
@@ -238,6 +337,9 @@ mod tests {
                 to: h!("a6"),
             })
         );
+
+        assert_eq!(San::parse("a5a6k"), Err("invalid to rank".to_string()));
+        assert_eq!(San::parse("f10f11k"), Err("invalid promotion character: k".to_string()));
     }
 
     #[test]
@@ -380,6 +482,26 @@ mod tests {
       assert_eq!(San::from(&"f10f6q".to_string()), Err("invalid promotion position: f6".to_string()));
     }
 
+    #[test]
+    fn flip_colors() {
+        let glinski = Variant::Glinski.geometry();
+        let shafran = Variant::Shafran.geometry();
+
+        assert_eq!(s!("g4g6").flip_colors(glinski), s!("e7e5"));
+        assert_eq!(s!("f10f11q").flip_colors(glinski), s!("f2f1q"));
+        assert_eq!(s!("b1a1").flip_colors(glinski).flip_colors(glinski), s!("b1a1"));
+        assert_eq!(s!("g2g3").flip_colors(shafran), s!("e10e9"));
+    }
+
+    #[test]
+    fn mirror() {
+        let glinski = Variant::Glinski.geometry();
+
+        assert_eq!(s!("g4g6").mirror(glinski), s!("e4e6"));
+        assert_eq!(s!("a5a6q").mirror(glinski), s!("l5l6q"));
+        assert_eq!(s!("d1e3").mirror(glinski).mirror(glinski), s!("d1e3"));
+    }
+
     #[test]
     fn test_display_string_format() {
         assert_eq!(s!("a1a2").to_string(), "a1a2".to_string());
@@ -387,5 +509,35 @@ mod tests {
         assert_eq!(s!("f10f11r").to_string(), "f10f11r".to_string());
         assert_eq!(s!("f10f11b").to_string(), "f10f11b".to_string());
         assert_eq!(s!("f10f11n").to_string(), "f10f11n".to_string());
+        assert_eq!(San { from: h!("f10"), promotion: Some(PromotionPiece::King), to: h!("f11") }.to_string(), "f10f11k".to_string());
+    }
+
+    #[test]
+    fn drops() {
+        let san = San::parse("N@f6").unwrap();
+
+        assert_eq!(san, San { from: DROP_OFFSET + 1, promotion: None, to: h!("f6") });
+        assert_eq!(san.dropped(), Some(Piece::WhiteKnight));
+        assert_eq!(san.to_string(), "N@f6");
+        assert_eq!(San::new_drop(Piece::BlackKnight, h!("f6")), Some(san));
+        assert_eq!(San::new_drop(Piece::WhiteKing, h!("f6")), None);
+        assert_eq!(s!("P@a2").mirror(Variant::Crazyhouse.geometry()), s!("P@l2"));
+        assert_eq!(s!("g4g6").dropped(), None);
+
+        assert_eq!(San::parse("K@f6"), Err("invalid drop piece: K".to_string()));
+        assert_eq!(San::parse("n@f6"), Err("invalid drop piece: n".to_string()));
+        assert_eq!(San::parse("Q@f12"), Err("invalid to position: f12".to_string()));
+    }
+
+    #[test]
+    fn standard_traits() {
+        assert_eq!("g4g6".parse::<San>(), Ok(s!("g4g6")));
+        assert_eq!(San::try_from("f10f11q"), Ok(s!("f10f11q")));
+        assert_eq!("whoops".parse::<San>(), San::parse("whoops"));
+
+        let mut seen = std::collections::HashSet::new();
+
+        assert!(seen.insert(s!("g4g6")));
+        assert!(!seen.insert(s!("g4g6")));
     }
 }
\ No newline at end of file
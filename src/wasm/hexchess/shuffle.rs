@@ -0,0 +1,201 @@
+use crate::constants::Piece;
+use crate::h;
+use crate::hexchess::geometry::Cell;
+use crate::hexchess::hexchess::Hexchess;
+use crate::hexchess::utils::swap_color;
+use crate::hexchess::variant::Variant;
+use std::sync::OnceLock;
+
+/// Cells of white's back pieces, along the first rank and then up the f-file.
+/// Black's pieces are always placed on the reflection of these cells.
+const BACK_CELLS: [u8; 9] = [
+    h!("c1"),
+    h!("d1"),
+    h!("e1"),
+    h!("f1"),
+    h!("g1"),
+    h!("h1"),
+    h!("i1"),
+    h!("f2"),
+    h!("f3"),
+];
+
+/// Cells the king may start on, behind the rest of the back pieces
+const KING_CELLS: [u8; 7] = [h!("c1"), h!("d1"), h!("e1"), h!("f1"), h!("g1"), h!("h1"), h!("i1")];
+
+/// Kinds of back pieces, in the order arrangements are enumerated
+const PIECES: [Piece; 5] = [
+    Piece::WhiteBishop,
+    Piece::WhiteKing,
+    Piece::WhiteKnight,
+    Piece::WhiteQueen,
+    Piece::WhiteRook,
+];
+
+/// Back pieces of the initial position, as sorted indexes of `PIECES`
+const BACK_PIECES: [u8; 9] = [0, 0, 0, 1, 2, 2, 3, 4, 4];
+
+/// count the shuffled starting positions
+pub fn shuffle_count() -> u32 {
+    arrangements().len() as u32
+}
+
+/// find the index of a shuffled starting position
+pub fn shuffle_index(hexchess: &Hexchess) -> Option<u32> {
+    let mut arrangement = [0u8; 9];
+
+    for (i, cell) in BACK_CELLS.iter().enumerate() {
+        let piece = hexchess.board[*cell as usize]?;
+
+        arrangement[i] = PIECES.iter().position(|p| *p == piece)? as u8;
+    }
+
+    let index = arrangements().iter().position(|a| *a == arrangement)? as u32;
+
+    match shuffle_position(index) {
+        Ok(start) if start == *hexchess => Some(index),
+        _ => None,
+    }
+}
+
+/// get a shuffled starting position by it's index
+pub fn shuffle_position(index: u32) -> Result<Hexchess, String> {
+    let arrangement = match arrangements().get(index as usize) {
+        Some(arrangement) => *arrangement,
+        None => return Err(format!("invalid shuffle index: {}", index)),
+    };
+
+    let mut hexchess = Hexchess::init_variant(Variant::Glinski);
+    let geometry = hexchess.geometry();
+
+    for (cell, piece) in BACK_CELLS.iter().zip(arrangement) {
+        let piece = PIECES[piece as usize];
+
+        hexchess.board[*cell as usize] = Some(piece);
+        hexchess.board[geometry.flip(*cell) as usize] = Some(swap_color(&piece));
+    }
+
+    Ok(hexchess)
+}
+
+/// get a shuffled starting position from a seed, the same seed always giving the same position
+pub fn shuffle_seeded(seed: u64) -> Hexchess {
+    // splitmix64, so neighboring seeds give unrelated positions
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+
+    shuffle_position((z % shuffle_count() as u64) as u32).unwrap()
+}
+
+/// get legal arrangements of back pieces, enumerated once
+fn arrangements() -> &'static [[u8; 9]] {
+    static ARRANGEMENTS: OnceLock<Vec<[u8; 9]>> = OnceLock::new();
+
+    ARRANGEMENTS.get_or_init(enumerate)
+}
+
+/// enumerate legal arrangements of back pieces, in lexicographic order
+fn enumerate() -> Vec<[u8; 9]> {
+    let mut result: Vec<[u8; 9]> = vec![];
+    let mut arrangement = BACK_PIECES;
+
+    loop {
+        if is_legal(&arrangement) {
+            result.push(arrangement);
+        }
+
+        // step to the next permutation, stopping after the last
+        let pivot = match (0..8).rev().find(|i| arrangement[*i] < arrangement[i + 1]) {
+            Some(pivot) => pivot,
+            None => return result,
+        };
+
+        let swap = (pivot + 1..9).rev().find(|i| arrangement[*i] > arrangement[pivot]).unwrap();
+
+        arrangement.swap(pivot, swap);
+        arrangement[pivot + 1..].reverse();
+    }
+}
+
+/// test if bishops start on three different cell shades, and the king is on the first rank
+fn is_legal(arrangement: &[u8; 9]) -> bool {
+    let mut shades = [false; 3];
+
+    for (cell, piece) in BACK_CELLS.iter().zip(arrangement) {
+        match PIECES[*piece as usize] {
+            Piece::WhiteBishop => {
+                let (q, r) = Cell::new(*cell).unwrap().axial();
+                let shade = (q - r).rem_euclid(3) as usize;
+
+                if shades[shade] {
+                    return false;
+                }
+
+                shades[shade] = true;
+            },
+            Piece::WhiteKing if !KING_CELLS.contains(cell) => return false,
+            _ => {},
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count() {
+        assert_eq!(shuffle_count(), 3780);
+    }
+
+    #[test]
+    fn initial_position_is_included() {
+        let index = shuffle_index(&Hexchess::init()).unwrap();
+        assert_eq!(index, 3520);
+
+        assert_eq!(shuffle_position(index), Ok(Hexchess::init()));
+    }
+
+    #[test]
+    fn every_position() {
+        for index in 0..shuffle_count() {
+            let hexchess = shuffle_position(index).unwrap();
+
+            assert_eq!(hexchess.validate(), Ok(()));
+
+            for cell in BACK_CELLS {
+                let black = hexchess.geometry().flip(cell);
+
+                assert_eq!(hexchess.board[black as usize], hexchess.board[cell as usize].map(|piece| swap_color(&piece)));
+            }
+
+            assert_eq!(Hexchess::parse(&hexchess.to_string()), Ok(hexchess));
+            assert_eq!(shuffle_index(&hexchess), Some(index));
+        }
+    }
+
+    #[test]
+    fn stable_numbering() {
+        assert_eq!(shuffle_position(0).unwrap().to_string(), "k/brn/b1r1n/b5q/ppppppppp/11/5P5/4P1P4/3P1R1P3/2P2R2P2/1PBBBKNNQP1 w - 0 1");
+        assert_eq!(shuffle_position(shuffle_count()), Err(format!("invalid shuffle index: {}", shuffle_count())));
+    }
+
+    #[test]
+    fn seeded() {
+        assert_eq!(shuffle_seeded(42), shuffle_seeded(42));
+        assert_ne!(shuffle_seeded(1), shuffle_seeded(2));
+    }
+
+    #[test]
+    fn index_of_other_positions() {
+        let mut hexchess = Hexchess::init();
+        hexchess.apply("g4g5").unwrap();
+
+        assert_eq!(shuffle_index(&hexchess), None);
+        assert_eq!(shuffle_index(&Hexchess::new()), None);
+    }
+}
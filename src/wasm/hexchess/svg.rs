@@ -0,0 +1,380 @@
+use crate::hexchess::geometry::Cell;
+use crate::hexchess::hexchess::Hexchess;
+use crate::hexchess::layout::{Layout, Orientation, Point};
+use crate::hexchess::san::San;
+use crate::hexchess::variant::BoardGeometry;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+#[cfg(feature = "wasm")]
+use tsify_next::Tsify;
+
+/// Cell shades, from lightest to darkest
+const SHADES: [&str; 3] = ["#fed7aa", "#fdba74", "#ca8a04"];
+
+const ARROW_COLOR: &str = "#15803d";
+const CHECK_COLOR: &str = "#ef4444";
+const HIGHLIGHT_COLOR: &str = "#3b82f6";
+const LAST_MOVE_COLOR: &str = "#facc15";
+
+/// Arrow drawn between two positions
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Arrow {
+    /// From position index, 0..91
+    pub from: u8,
+
+    /// Target position index, 0..91
+    pub to: u8,
+}
+
+/// Options for rendering a board diagram
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct SvgOptions {
+    /// Arrows drawn over the board
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub arrows: Vec<Arrow>,
+
+    /// Shade the king of the current turn when it is in check
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub check: bool,
+
+    /// Label files and ranks along the edge of the board
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub coordinates: bool,
+
+    /// Render the board from black's perspective
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub flipped: bool,
+
+    /// Position indexes to highlight
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub highlights: Vec<u8>,
+
+    /// Move to highlight as the last one played
+    #[cfg_attr(feature = "wasm", tsify(optional, type = "SanStruct"))]
+    pub last_move: Option<San>,
+
+    /// Distance from the center of a cell to it's corners
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub size: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            arrows: vec![],
+            check: true,
+            coordinates: true,
+            flipped: false,
+            highlights: vec![],
+            last_move: None,
+            size: 20.0,
+        }
+    }
+}
+
+/// render a standalone svg diagram of a position
+pub fn render_svg(hexchess: &Hexchess, options: &SvgOptions) -> String {
+    let layout = Layout::new(options.size, Orientation::Flat, options.flipped);
+    let geometry = hexchess.geometry();
+    let (min, max) = bounds(&layout, geometry);
+    let mut svg = String::new();
+
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{x} {y} {w} {h}" width="{w}" height="{h}">"#,
+        x = num(min.x),
+        y = num(min.y),
+        w = num(max.x - min.x),
+        h = num(max.y - min.y),
+    );
+
+    // cells
+    for cell in geometry.cells.iter().filter_map(Cell::new) {
+        let (q, r) = cell.axial();
+        let shade = SHADES[(q - r).rem_euclid(3) as usize];
+
+        let _ = write!(svg, r#"<polygon points="{}" fill="{}"/>"#, points(&layout, cell), shade);
+    }
+
+    // overlays
+    let mut overlays: Vec<(u8, &str)> = vec![];
+
+    if let Some(san) = options.last_move {
+        overlays.push((san.from, LAST_MOVE_COLOR));
+        overlays.push((san.to, LAST_MOVE_COLOR));
+    }
+
+    for position in options.highlights.iter() {
+        overlays.push((*position, HIGHLIGHT_COLOR));
+    }
+
+    if options.check && hexchess.is_check() {
+        if let Some(king) = hexchess.find_king(hexchess.turn) {
+            overlays.push((king, CHECK_COLOR));
+        }
+    }
+
+    for (position, color) in overlays {
+        if let Some(cell) = Cell::new(position) {
+            let _ = write!(
+                svg,
+                r#"<polygon points="{}" fill="{}" fill-opacity="0.6"/>"#,
+                points(&layout, cell),
+                color,
+            );
+        }
+    }
+
+    // coordinates
+    if options.coordinates {
+        let font_size = num(options.size * 0.4);
+
+        for (label, cell) in edge_labels(&layout, geometry) {
+            let center = layout.center(cell);
+
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="#78350f" text-anchor="middle">{}</text>"##,
+                num(center.x - options.size * 0.45),
+                num(center.y + options.size * 0.65),
+                font_size,
+                label,
+            );
+        }
+    }
+
+    // pieces
+    for cell in geometry.cells.iter().filter_map(Cell::new) {
+        let piece = match hexchess.board[cell.index() as usize] {
+            Some(piece) => piece,
+            None => continue,
+        };
+
+        let center = layout.center(cell);
+
+        let _ = write!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="{}" fill="#000" text-anchor="middle" dominant-baseline="central">{}</text>"##,
+            num(center.x),
+            num(center.y),
+            num(options.size * 1.2),
+            piece.glyph(),
+        );
+    }
+
+    // arrows
+    if !options.arrows.is_empty() {
+        let _ = write!(
+            svg,
+            r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="3" markerHeight="3" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#,
+            ARROW_COLOR,
+        );
+    }
+
+    for arrow in options.arrows.iter() {
+        let (from, to) = match (Cell::new(arrow.from), Cell::new(arrow.to)) {
+            (Some(from), Some(to)) => (layout.center(from), layout.center(to)),
+            _ => continue,
+        };
+
+        let _ = write!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-opacity="0.8" marker-end="url(#arrowhead)"/>"#,
+            num(from.x),
+            num(from.y),
+            num(to.x),
+            num(to.y),
+            ARROW_COLOR,
+            num(options.size * 0.3),
+        );
+    }
+
+    svg.push_str("</svg>");
+
+    svg
+}
+
+/// find the top left and bottom right corners of the box around a board's cells
+fn bounds(layout: &Layout, geometry: &BoardGeometry) -> (Point, Point) {
+    let mut min = Point { x: f64::MAX, y: f64::MAX };
+    let mut max = Point { x: f64::MIN, y: f64::MIN };
+
+    for point in geometry.cells.iter().filter_map(Cell::new).flat_map(|cell| layout.corners(cell)) {
+        min = Point { x: min.x.min(point.x), y: min.y.min(point.y) };
+        max = Point { x: max.x.max(point.x), y: max.y.max(point.y) };
+    }
+
+    (min, max)
+}
+
+/// find the cells nearest the bottom and left edges of the diagram to label
+fn edge_labels(layout: &Layout, geometry: &BoardGeometry) -> Vec<(String, Cell)> {
+    let mut files: Vec<(char, Cell, Point)> = vec![];
+    let mut ranks: Vec<(String, Cell, Point)> = vec![];
+
+    for index in geometry.cells.iter() {
+        let (cell, position) = match (Cell::new(index), geometry.position_name(index)) {
+            (Some(cell), Some(position)) => (cell, position),
+            _ => continue,
+        };
+
+        let file = position.chars().next().unwrap();
+        let rank = position[1..].to_string();
+        let center = layout.center(cell);
+
+        match files.iter_mut().find(|(f, _, _)| *f == file) {
+            Some(entry) => if center.y > entry.2.y {
+                *entry = (file, cell, center);
+            },
+            None => files.push((file, cell, center)),
+        }
+
+        match ranks.iter_mut().find(|(r, _, _)| *r == rank) {
+            Some(entry) => if center.x < entry.2.x {
+                *entry = (rank.clone(), cell, center);
+            },
+            None => ranks.push((rank, cell, center)),
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(file, cell, _)| (file.to_string(), cell))
+        .chain(ranks.into_iter().map(|(rank, cell, _)| (rank, cell)))
+        .collect()
+}
+
+/// format a number with at most two decimal places
+fn num(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+/// format the corners of a cell as polygon points
+fn points(layout: &Layout, cell: Cell) -> String {
+    layout
+        .corners(cell)
+        .iter()
+        .map(|point| format!("{},{}", num(point.x), num(point.y)))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{h, s};
+    use crate::hexchess::variant::Variant;
+    use super::*;
+
+    #[test]
+    fn initial_position() {
+        let svg = render_svg(&Hexchess::init(), &SvgOptions::default());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 340 381.05" width="340" height="381.05">"#));
+        assert_eq!(svg.matches("<polygon").count(), 91);
+        assert_eq!(svg.matches('♙').count(), 9);
+        assert_eq!(svg.matches('♟').count(), 9);
+        assert_eq!(svg.matches('♔').count(), 1);
+        assert_eq!(svg.matches('♚').count(), 1);
+
+        for shade in SHADES {
+            assert_eq!(svg.matches(shade).count(), match shade {
+                "#fed7aa" => 31,
+                _ => 30,
+            });
+        }
+    }
+
+    #[test]
+    fn coordinates() {
+        let svg = render_svg(&Hexchess::new(), &SvgOptions::default());
+
+        assert_eq!(svg.matches("<text").count(), 22);
+        assert!(svg.contains(">l</text>"));
+        assert!(svg.contains(">11</text>"));
+
+        let svg = render_svg(&Hexchess::new(), &SvgOptions { coordinates: false, ..SvgOptions::default() });
+
+        assert_eq!(svg.matches("<text").count(), 0);
+    }
+
+    #[test]
+    fn variant_board() {
+        let hexchess = Hexchess::new_variant(Variant::Mini);
+
+        let svg = render_svg(&hexchess, &SvgOptions::default());
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="60 69.28 220 242.49" width="220" height="242.49">"#));
+        assert_eq!(svg.matches("<polygon").count(), 37);
+        assert_eq!(svg.matches("<text").count(), 14);
+        assert!(svg.contains(">g</text>"));
+        assert!(!svg.contains(">l</text>"));
+    }
+
+    #[test]
+    fn flipped() {
+        let white = render_svg(&Hexchess::init(), &SvgOptions::default());
+        let black = render_svg(&Hexchess::init(), &SvgOptions { flipped: true, ..SvgOptions::default() });
+
+        assert_ne!(white, black);
+    }
+
+    #[test]
+    fn overlays() {
+        let svg = render_svg(&Hexchess::init(), &SvgOptions {
+            highlights: vec![h!("f6"), h!("f7")],
+            last_move: Some(s!("g4g6")),
+            ..SvgOptions::default()
+        });
+
+        assert_eq!(svg.matches(LAST_MOVE_COLOR).count(), 2);
+        assert_eq!(svg.matches(HIGHLIGHT_COLOR).count(), 2);
+        assert_eq!(svg.matches(CHECK_COLOR).count(), 0);
+    }
+
+    #[test]
+    fn check_indicator() {
+        let hexchess = Hexchess::parse("1/3/5/7/9/11/5k5/11/5Q5/11/5K5 b - 0 1").unwrap();
+
+        let svg = render_svg(&hexchess, &SvgOptions::default());
+        assert_eq!(svg.matches(CHECK_COLOR).count(), 1);
+
+        let svg = render_svg(&hexchess, &SvgOptions { check: false, ..SvgOptions::default() });
+        assert_eq!(svg.matches(CHECK_COLOR).count(), 0);
+    }
+
+    #[test]
+    fn arrows() {
+        let svg = render_svg(&Hexchess::init(), &SvgOptions {
+            arrows: vec![
+                Arrow { from: h!("f5"), to: h!("f7") },
+                Arrow { from: h!("d1"), to: h!("e3") },
+            ],
+            ..SvgOptions::default()
+        });
+
+        assert_eq!(svg.matches("<marker").count(), 1);
+        assert_eq!(svg.matches("<line").count(), 2);
+    }
+
+    #[test]
+    fn number_formatting() {
+        assert_eq!(num(1.0), "1");
+        assert_eq!(num(1.5), "1.5");
+        assert_eq!(num(1.23456), "1.23");
+        assert_eq!(num(-0.001), "0");
+    }
+}
@@ -0,0 +1,240 @@
+use crate::constants::{Color, Piece};
+use crate::hexchess::geometry::Cell;
+use crate::hexchess::hexchess::Hexchess;
+use crate::hexchess::utils::get_color;
+use crate::hexchess::variant::BoardGeometry;
+
+/// Number of lines taken up by the board, excluding file labels
+const LINES: usize = 21;
+
+/// Columns between neighboring files
+const FILE_WIDTH: usize = 4;
+
+/// Columns reserved on the left for rank labels
+const MARGIN: usize = 3;
+
+/// 256-color backgrounds of cell shades, from lightest to darkest
+const SHADES: [u8; 3] = [223, 215, 178];
+
+const HIGHLIGHT: u8 = 75;
+
+/// Options for rendering a board as text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextOptions {
+    /// Color cells and pieces with ansi escape codes
+    pub ansi: bool,
+
+    /// Render the board from black's perspective
+    pub flipped: bool,
+
+    /// Position indexes to highlight
+    pub highlights: Vec<u8>,
+
+    /// Draw pieces with unicode chess glyphs instead of fen letters
+    pub unicode: bool,
+}
+
+/// render a position as a hexagonal text diagram with file and rank labels
+pub fn render_text(hexchess: &Hexchess, options: &TextOptions) -> String {
+    let geometry = hexchess.geometry();
+
+    // each line is a list of (column, text, visible width) segments
+    let mut lines: Vec<Vec<(usize, String, usize)>> = vec![vec![]; LINES + 1];
+    let mut rank_labels: Vec<(String, usize, usize)> = vec![];
+
+    for (cell, name) in cells(geometry) {
+        let (line, column) = locate(cell, options.flipped);
+        let (q, r) = cell.axial();
+
+        let symbol = match hexchess.board[cell.index() as usize] {
+            Some(piece) => symbol(&piece, options.unicode),
+            None => match options.unicode {
+                true => '·',
+                false => '.',
+            },
+        };
+
+        let highlighted = options.highlights.contains(&cell.index());
+
+        let segment = match (options.ansi, highlighted) {
+            (true, _) => {
+                let background = match highlighted {
+                    true => HIGHLIGHT,
+                    false => SHADES[(q - r).rem_euclid(3) as usize],
+                };
+
+                let foreground = match hexchess.board[cell.index() as usize] {
+                    Some(piece) if get_color(&piece) == Color::White => "1;38;5;231",
+                    _ => "38;5;16",
+                };
+
+                format!("\x1b[48;5;{};{}m {} \x1b[0m", background, foreground, symbol)
+            },
+            (false, true) => format!("[{}]", symbol),
+            (false, false) => format!(" {} ", symbol),
+        };
+
+        lines[line].push((column - 1, segment, 3));
+
+        // label each rank beside it's left-most cell
+        let rank = name[1..].to_string();
+
+        match rank_labels.iter_mut().find(|(label, _, _)| *label == rank) {
+            Some(entry) => if column < entry.2 {
+                *entry = (rank, line, column);
+            },
+            None => rank_labels.push((rank, line, column)),
+        }
+    }
+
+    for (label, line, column) in rank_labels {
+        lines[line].push((column - 1 - label.len(), label.clone(), label.len()));
+    }
+
+    // label files beneath the board
+    let cells = cells(geometry);
+    let bottom = cells.iter().map(|(cell, _)| locate(*cell, options.flipped).0).max().unwrap_or(LINES - 1);
+
+    for (cell, name) in cells {
+        if &name[1..] == "1" {
+            let (_, column) = locate(cell, options.flipped);
+
+            lines[bottom + 1].push((column, name[..1].to_string(), 1));
+        }
+    }
+
+    let mut output: Vec<String> = vec![];
+
+    for mut segments in lines {
+        segments.sort_by_key(|(column, _, _)| *column);
+
+        let mut line = String::new();
+        let mut cursor = 0;
+
+        for (column, text, width) in segments {
+            line.push_str(&" ".repeat(column.saturating_sub(cursor)));
+            line.push_str(&text);
+            cursor = column.max(cursor) + width;
+        }
+
+        output.push(line.trim_end().to_string());
+    }
+
+    // smaller boards leave lines above and below them empty
+    let first = output.iter().position(|line| !line.is_empty()).unwrap_or(0);
+    let last = output.iter().rposition(|line| !line.is_empty()).map_or(0, |n| n + 1);
+
+    output[first..last].join("\n")
+}
+
+/// get the cells of a board along with their position names
+fn cells(geometry: &BoardGeometry) -> Vec<(Cell, String)> {
+    geometry
+        .cells
+        .iter()
+        .filter_map(|position| Some((Cell::new(position)?, geometry.position_name(position)?)))
+        .collect()
+}
+
+/// find the line and column of a cell's symbol
+fn locate(cell: Cell, flipped: bool) -> (usize, usize) {
+    let (q, r) = match flipped {
+        true => {
+            let (q, r) = cell.axial();
+            (-q, -r)
+        },
+        false => cell.axial(),
+    };
+
+    let line = (2 * r + q + 10) as usize;
+    let column = MARGIN + 1 + FILE_WIDTH * (q + 5) as usize;
+
+    (line, column)
+}
+
+/// get the character used to draw a piece
+fn symbol(piece: &Piece, unicode: bool) -> char {
+    match unicode {
+        true => piece.glyph(),
+        false => piece.to_string().chars().next().unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::h;
+    use crate::hexchess::variant::Variant;
+    use super::*;
+
+    #[test]
+    fn initial_position() {
+        let text = render_text(&Hexchess::init(), &TextOptions::default());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 22);
+        assert_eq!(lines[0], "                     11 b");
+        assert_eq!(lines[21], "    a   b   c   d   e   f   g   h   i   k   l");
+        assert_eq!(text.matches('P').count(), 9);
+        assert_eq!(text.matches('p').count(), 9);
+        assert_eq!(text.matches('.').count(), 91 - 36);
+    }
+
+    #[test]
+    fn every_cell_is_drawn() {
+        let text = render_text(&Hexchess::new(), &TextOptions::default());
+
+        assert_eq!(text.matches('.').count(), 91);
+    }
+
+    #[test]
+    fn flipped() {
+        let text = render_text(&Hexchess::init(), &TextOptions { flipped: true, ..TextOptions::default() });
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "                        B");
+        assert_eq!(lines[20], "                     11 b");
+        assert_eq!(lines[21], "    l   k   i   h   g   f   e   d   c   b   a");
+    }
+
+    #[test]
+    fn variant_board() {
+        let text = render_text(&Hexchess::init_variant(Variant::Mini), &TextOptions::default());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[0], "                      7 b");
+        assert_eq!(lines[13], "            a   b   c   d   e   f   g");
+        assert_eq!(text.matches('.').count(), 37 - 28);
+    }
+
+    #[test]
+    fn unicode() {
+        let text = render_text(&Hexchess::init(), &TextOptions { unicode: true, ..TextOptions::default() });
+
+        assert_eq!(text.matches('♙').count(), 9);
+        assert_eq!(text.matches('♚').count(), 1);
+        assert_eq!(text.matches('·').count(), 91 - 36);
+    }
+
+    #[test]
+    fn highlights() {
+        let text = render_text(&Hexchess::new(), &TextOptions {
+            highlights: vec![h!("f6"), h!("f7")],
+            ..TextOptions::default()
+        });
+
+        assert_eq!(text.matches("[.]").count(), 2);
+    }
+
+    #[test]
+    fn ansi() {
+        let text = render_text(&Hexchess::new(), &TextOptions {
+            ansi: true,
+            highlights: vec![h!("f6")],
+            ..TextOptions::default()
+        });
+
+        assert_eq!(text.matches("\x1b[0m").count(), 91);
+        assert_eq!(text.matches("\x1b[48;5;75;").count(), 1);
+    }
+}
@@ -26,6 +26,36 @@ pub fn get_color(piece: &Piece) -> Color {
     }
 }
 
+/// get the same piece of the opposite color
+pub fn swap_color(piece: &Piece) -> Piece {
+    match piece {
+        Piece::WhitePawn => Piece::BlackPawn,
+        Piece::WhiteKnight => Piece::BlackKnight,
+        Piece::WhiteBishop => Piece::BlackBishop,
+        Piece::WhiteRook => Piece::BlackRook,
+        Piece::WhiteQueen => Piece::BlackQueen,
+        Piece::WhiteKing => Piece::BlackKing,
+        Piece::BlackPawn => Piece::WhitePawn,
+        Piece::BlackKnight => Piece::WhiteKnight,
+        Piece::BlackBishop => Piece::WhiteBishop,
+        Piece::BlackRook => Piece::WhiteRook,
+        Piece::BlackQueen => Piece::WhiteQueen,
+        Piece::BlackKing => Piece::WhiteKing,
+    }
+}
+
+/// test if a piece slides along a direction
+pub fn slides_along(piece: &Piece, direction: u8) -> bool {
+    let diagonal = direction % 2 == 1;
+
+    match piece {
+        Piece::BlackBishop | Piece::WhiteBishop => diagonal,
+        Piece::BlackRook | Piece::WhiteRook => !diagonal,
+        Piece::BlackQueen | Piece::WhiteQueen => true,
+        _ => false,
+    }
+}
+
 /// test if position is black en passant target
 pub fn is_legal_black_en_passant(position: &u8) -> bool {
     match position {
@@ -300,6 +330,14 @@ pub fn to_position(index: &u8) -> &'static str {
     }
 }
 
+/// convert index to position name, if it is on the board
+pub fn try_to_position(index: &u8) -> Option<&'static str> {
+    match index {
+        0..=90 => Some(to_position(index)),
+        _ => None,
+    }
+}
+
 // step along the hexboard graph
 pub fn step(from: u8, direction: u8) -> Option<u8> {
     HEXBOARD_GRAPH[from as usize][direction as usize]
@@ -311,7 +349,7 @@ pub fn walk(hexchess: &Hexchess, from: u8, direction: u8, color: &Color) -> Vec<
     let mut position: u8 = from;
 
     loop {
-        position = match step(position, direction) {
+        position = match hexchess.geometry().step(position, direction) {
             Some(index) => index,
             None => return path // <- end of board
         };
@@ -333,6 +371,36 @@ pub fn walk(hexchess: &Hexchess, from: u8, direction: u8, color: &Color) -> Vec<
     }
 }
 
+/// play a deterministic pseudo-random game from the initial position, returning it's moves
+#[cfg(test)]
+pub fn playout(seed: u64, plies: usize) -> Vec<crate::hexchess::san::San> {
+    playout_from(Hexchess::init(), seed, plies)
+}
+
+/// play a deterministic pseudo-random game from a position, returning it's moves
+#[cfg(test)]
+pub fn playout_from(mut hexchess: Hexchess, seed: u64, plies: usize) -> Vec<crate::hexchess::san::San> {
+    let mut state = seed;
+    let mut result = vec![];
+
+    for _ in 0..plies {
+        let moves = hexchess.current_moves();
+
+        if moves.is_empty() {
+            break;
+        }
+
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+
+        let san = moves[(state >> 33) as usize % moves.len()];
+
+        hexchess.apply_move(&san).unwrap();
+        result.push(san);
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +506,14 @@ mod tests {
         to_position(&91);
     }
 
+    #[test]
+    fn test_try_to_position() {
+        assert_eq!(try_to_position(&0), Some("f11"));
+        assert_eq!(try_to_position(&90), Some("l1"));
+        assert_eq!(try_to_position(&91), None);
+        assert_eq!(try_to_position(&255), None);
+    }
+
     mod walk {
         use crate::h;
         use super::*;
@@ -0,0 +1,761 @@
+use crate::constants::{Color, INITIAL_POSITION, PromotionPiece};
+use crate::h;
+use crate::hexchess::geometry::{Cell, Direction, FILES};
+use crate::hexchess::san::{parse_drop, San};
+use crate::hexchess::utils::{index, step, to_position};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
+use tsify_next::Tsify;
+
+/// Number of cells in each fen row of the full 91 cell board
+const ROW_LENGTHS: [u8; 11] = [1, 3, 5, 7, 9, 11, 11, 11, 11, 11, 11];
+
+/// Fen characters of each castling right, in the order of `BoardGeometry::castling`
+pub const CASTLING_RIGHTS: [char; 4] = ['K', 'Q', 'k', 'q'];
+
+/// Set of position indexes
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(into = "Vec<u8>", try_from = "Vec<u8>"))]
+pub struct CellSet(u128);
+
+impl CellSet {
+    /// create a set from position indexes
+    pub const fn from_positions(positions: &[u8]) -> Self {
+        let mut bits: u128 = 0;
+        let mut i = 0;
+
+        while i < positions.len() {
+            bits |= 1 << positions[i];
+            i += 1;
+        }
+
+        Self(bits)
+    }
+
+    /// remove the positions of another set
+    pub const fn without(self, other: CellSet) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// add a position to the set
+    pub fn insert(&mut self, position: u8) {
+        self.0 |= 1 << position;
+    }
+
+    /// remove a position from the set
+    pub fn remove(&mut self, position: u8) {
+        self.0 &= !(1 << position);
+    }
+
+    /// test if a position is in the set
+    pub fn contains(&self, position: u8) -> bool {
+        position < 128 && self.0 & (1 << position) != 0
+    }
+
+    /// iterate over positions in the set, in fen order
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0u8..91u8).filter(|n| self.contains(*n))
+    }
+
+    /// count the positions in the set before a position, in fen order
+    pub fn count_before(&self, position: u8) -> usize {
+        (self.0 & ((1 << position) - 1)).count_ones() as usize
+    }
+
+    /// count the positions in the set
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// test if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl FromIterator<u8> for CellSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(positions: I) -> Self {
+        let mut result = Self::default();
+
+        for position in positions {
+            result.insert(position);
+        }
+
+        result
+    }
+}
+
+impl From<CellSet> for Vec<u8> {
+    fn from(cells: CellSet) -> Self {
+        cells.iter().collect()
+    }
+}
+
+impl TryFrom<Vec<u8>> for CellSet {
+    type Error = String;
+
+    fn try_from(positions: Vec<u8>) -> Result<Self, Self::Error> {
+        match positions.iter().find(|position| **position > 90) {
+            Some(position) => Err(format!("invalid position index: {}", position)),
+            None => Ok(positions.into_iter().collect()),
+        }
+    }
+}
+
+/// Castling between a king and rook on their starting positions
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Castling {
+    /// Color of the castling pieces
+    pub color: Color,
+
+    /// Starting position of the king
+    pub king: u8,
+
+    /// Starting position of the rook
+    pub rook: u8,
+
+    /// Position the king castles to
+    pub king_to: u8,
+
+    /// Position the rook castles to
+    pub rook_to: u8,
+
+    /// Positions between the king and rook in order from the king, these must
+    /// be empty, and those the king crosses must not be attacked
+    pub path: &'static [u8],
+}
+
+/// Cells and pawn rules of a variant's board, described as data
+///
+/// Every supported board fits within the 91 cell hexagon, so positions are
+/// always indexed the same way. Cells outside of `cells` are never stepped
+/// onto, and a `Board` only stores pieces for the cells within it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BoardGeometry {
+    /// Cells on the board
+    pub cells: CellSet,
+
+    /// Initial position in fen
+    pub initial_position: &'static str,
+
+    /// Cells pawns may double step from, white then black
+    pub double_step: [CellSet; 2],
+
+    /// Cells whose pawn double steps set an en passant position, white then black
+    pub en_passant: [CellSet; 2],
+
+    /// Cells pawns promote on, white then black
+    pub promotion: [CellSet; 2],
+
+    /// Direction pawns advance, white then black
+    pub pawn_advance: [Direction; 2],
+
+    /// Directions pawns capture, white then black
+    pub pawn_captures: [[Direction; 2]; 2],
+
+    /// Castling moves, white kingside, white queenside, black kingside, black queenside
+    pub castling: [Option<Castling>; 4],
+
+    /// Files and ranks that position names are shifted down by, compared to the 91 cell board
+    pub name_offset: (u8, u8),
+}
+
+impl BoardGeometry {
+    /// test if a position is on the board
+    pub fn contains(&self, position: u8) -> bool {
+        self.cells.contains(position)
+    }
+
+    /// reflect a position across the middle of it's file, swapping ends of the board
+    pub fn flip(&self, position: u8) -> u8 {
+        let file = &to_position(&position)[..1];
+        let column: Vec<u8> = self.cells.iter().filter(|n| &to_position(n)[..1] == file).collect();
+
+        match column.iter().position(|n| *n == position) {
+            Some(i) => column[column.len() - 1 - i],
+            None => position,
+        }
+    }
+
+    /// reflect a position across the f-file
+    pub fn mirror(&self, position: u8) -> u8 {
+        Cell::new(position).map(|cell| cell.mirror().index()).unwrap_or(position)
+    }
+
+    /// turn a position half way around the board, as seen by the other color
+    pub fn rotate(&self, position: u8) -> u8 {
+        self.mirror(self.flip(position))
+    }
+
+    /// format a move with this board's position names
+    pub fn format_san(&self, san: &San) -> String {
+        let name = |position: u8| self.position_name(position).unwrap_or_else(|| to_position(&position).to_string());
+
+        if let Some(piece) = san.dropped() {
+            return format!("{}@{}", piece, name(san.to));
+        }
+
+        let mut result = name(san.from) + &name(san.to);
+
+        if let Some(promotion) = san.promotion {
+            result.push_str(&promotion.to_string());
+        }
+
+        result
+    }
+
+    /// test if the board has any castling moves
+    pub fn has_castling(&self) -> bool {
+        self.castling.iter().any(|castling| castling.is_some())
+    }
+
+    /// test if a position may be double stepped from by a pawn
+    pub fn is_double_step(&self, position: u8, color: Color) -> bool {
+        self.double_step[side(color)].contains(position)
+    }
+
+    /// test if a pawn double step from a position sets an en passant position
+    pub fn sets_en_passant(&self, position: u8, color: Color) -> bool {
+        self.en_passant[side(color)].contains(position)
+    }
+
+    /// test if a position is passed over by a pawn double step
+    pub fn is_en_passant(&self, position: u8, color: Color) -> bool {
+        let behind = self.pawn_advance(color).opposite();
+
+        match self.step(position, behind) {
+            Some(start) => self.is_double_step(start, color) && self.step(position, self.pawn_advance(color)).is_some(),
+            None => false,
+        }
+    }
+
+    /// test if a pawn promotes on a position
+    pub fn is_promotion(&self, position: u8, color: Color) -> bool {
+        self.promotion[side(color)].contains(position)
+    }
+
+    /// get the direction pawns of a color advance
+    pub fn pawn_advance(&self, color: Color) -> Direction {
+        self.pawn_advance[side(color)]
+    }
+
+    /// get the directions pawns of a color capture
+    pub fn pawn_captures(&self, color: Color) -> [Direction; 2] {
+        self.pawn_captures[side(color)]
+    }
+
+    /// parse a move written with this board's position names, kings are not promoted to
+    pub fn parse_san(&self, source: &str) -> Result<San, String> {
+        if let Some((piece, to_name)) = source.split_once('@') {
+            return parse_drop(piece, to_name, |name| self.position_index(name));
+        }
+
+        let (from_name, rest) = split_position(source);
+        let (to_name, rest) = split_position(rest);
+
+        let from = match self.position_index(from_name) {
+            Some(from) => from,
+            None => return Err(format!("invalid from position: {}", from_name)),
+        };
+
+        let to = match self.position_index(to_name) {
+            Some(to) => to,
+            None => return Err(format!("invalid to position: {}", to_name)),
+        };
+
+        if from == to {
+            return Err("to and from positions are the same".to_string());
+        }
+
+        let promotion = match rest {
+            "" => None,
+            "k" => return Err(format!("invalid promotion piece: {}", rest)),
+            _ => Some(rest.parse::<PromotionPiece>()?),
+        };
+
+        if promotion.is_some() && !self.is_promotion(to, Color::White) && !self.is_promotion(to, Color::Black) {
+            return Err(format!("invalid promotion position: {}", to_name));
+        }
+
+        Ok(San { from, promotion, to })
+    }
+
+    /// get the index of a position on this board by name
+    pub fn position_index(&self, name: &str) -> Option<u8> {
+        let mut chars = name.chars();
+        let first = chars.next()?;
+        let file = FILES.iter().position(|f| *f == first)? + self.name_offset.0 as usize;
+        let rank = chars.as_str();
+
+        if rank.starts_with('0') {
+            return None;
+        }
+
+        let rank = rank.parse::<u8>().ok()? + self.name_offset.1;
+
+        index(&format!("{}{}", FILES.get(file)?, rank))
+            .ok()
+            .filter(|n| self.contains(*n))
+    }
+
+    /// get the name of a position on this board
+    pub fn position_name(&self, position: u8) -> Option<String> {
+        if !self.contains(position) {
+            return None;
+        }
+
+        let name = to_position(&position);
+        let file = FILES.iter().position(|f| name.starts_with(*f))? - self.name_offset.0 as usize;
+        let rank = name[1..].parse::<u8>().ok()? - self.name_offset.1;
+
+        Some(format!("{}{}", FILES[file], rank))
+    }
+
+    /// get cells on the board grouped by fen row
+    pub fn rows(&self) -> Vec<Vec<u8>> {
+        let mut result: Vec<Vec<u8>> = vec![];
+        let mut start: u8 = 0;
+
+        for length in ROW_LENGTHS {
+            let row: Vec<u8> = (start..start + length).filter(|n| self.contains(*n)).collect();
+
+            if !row.is_empty() {
+                result.push(row);
+            }
+
+            start += length;
+        }
+
+        result
+    }
+
+    /// step from a position in a direction, staying on the board
+    pub fn step(&self, from: u8, direction: impl Into<u8>) -> Option<u8> {
+        step(from, direction.into()).filter(|to| self.contains(*to))
+    }
+}
+
+/// Gliński's hexagonal chess
+const GLINSKI: BoardGeometry = BoardGeometry {
+    cells: CellSet(u128::MAX >> (128 - 91)),
+    initial_position: INITIAL_POSITION,
+    double_step: [
+        CellSet::from_positions(&[h!("b1"), h!("c2"), h!("d3"), h!("e4"), h!("f5"), h!("g4"), h!("h3"), h!("i2"), h!("k1")]),
+        CellSet::from_positions(&[h!("b7"), h!("c7"), h!("d7"), h!("e7"), h!("f7"), h!("g7"), h!("h7"), h!("i7"), h!("k7")]),
+    ],
+    en_passant: [
+        CellSet::from_positions(&[h!("c2"), h!("d3"), h!("e4"), h!("f5"), h!("g4"), h!("h3"), h!("i2"), h!("k1")]),
+        CellSet::from_positions(&[h!("c7"), h!("d7"), h!("e7"), h!("f7"), h!("g7"), h!("h7"), h!("i7"), h!("k7")]),
+    ],
+    promotion: [
+        CellSet::from_positions(&[h!("a6"), h!("b7"), h!("c8"), h!("d9"), h!("e10"), h!("f11"), h!("g10"), h!("h9"), h!("i8"), h!("k7"), h!("l6")]),
+        CellSet::from_positions(&[h!("a1"), h!("b1"), h!("c1"), h!("d1"), h!("e1"), h!("f1"), h!("g1"), h!("h1"), h!("i1"), h!("k1"), h!("l1")]),
+    ],
+    pawn_advance: [Direction::North, Direction::South],
+    pawn_captures: [[Direction::NorthWest, Direction::NorthEast], [Direction::SouthEast, Direction::SouthWest]],
+    castling: [None; 4],
+    name_offset: (0, 0),
+};
+
+/// McCooey's hexagonal chess
+const MCCOOEY: BoardGeometry = BoardGeometry {
+    cells: CellSet(u128::MAX >> (128 - 91)),
+    initial_position: "b/qbk/rnbnr/ppppppp/9/11/11/5P5/4PBP4/3PNBNP3/2PRQBKRP2 w - 0 1",
+    double_step: [
+        CellSet::from_positions(&[h!("c1"), h!("d2"), h!("e3"), h!("g3"), h!("h2"), h!("i1")]),
+        CellSet::from_positions(&[h!("c8"), h!("d8"), h!("e8"), h!("g8"), h!("h8"), h!("i8")]),
+    ],
+    en_passant: [
+        CellSet::from_positions(&[h!("c1"), h!("d2"), h!("e3"), h!("g3"), h!("h2"), h!("i1")]),
+        CellSet::from_positions(&[h!("c8"), h!("d8"), h!("e8"), h!("g8"), h!("h8"), h!("i8")]),
+    ],
+    promotion: GLINSKI.promotion,
+    pawn_advance: [Direction::North, Direction::South],
+    pawn_captures: [[Direction::NorthNorthWest, Direction::NorthNorthEast], [Direction::SouthSouthEast, Direction::SouthSouthWest]],
+    castling: [None; 4],
+    name_offset: (0, 0),
+};
+
+/// Shafran's hexagonal chess, on the 91 cell board without the a and l files
+/// or the bottom edge. Files and ranks are named from the remaining cells, so
+/// shafran's a1 is gliński's b2.
+const SHAFRAN: BoardGeometry = BoardGeometry {
+    cells: GLINSKI.cells.without(CellSet::from_positions(&[
+        h!("a1"), h!("a2"), h!("a3"), h!("a4"), h!("a5"), h!("a6"),
+        h!("l1"), h!("l2"), h!("l3"), h!("l4"), h!("l5"), h!("l6"),
+        h!("b1"), h!("c1"), h!("d1"), h!("e1"), h!("f1"), h!("g1"), h!("h1"), h!("i1"), h!("k1"),
+    ])),
+    initial_position: "b/bpk/qp1pb/np3pn/rp5pr/p7p/9/9/PPPPPPPPP/RNQBBKBNR w KQkq - 0 1",
+    double_step: [
+        CellSet::from_positions(&[h!("b3"), h!("c3"), h!("d3"), h!("e3"), h!("f3"), h!("g3"), h!("h3"), h!("i3"), h!("k3")]),
+        CellSet::from_positions(&[h!("b6"), h!("c7"), h!("d8"), h!("e9"), h!("f10"), h!("g9"), h!("h8"), h!("i7"), h!("k6")]),
+    ],
+    en_passant: [
+        CellSet::from_positions(&[h!("b3"), h!("c3"), h!("d3"), h!("e3"), h!("f3"), h!("g3"), h!("h3"), h!("i3"), h!("k3")]),
+        CellSet::from_positions(&[h!("b6"), h!("c7"), h!("d8"), h!("e9"), h!("f10"), h!("g9"), h!("h8"), h!("i7"), h!("k6")]),
+    ],
+    promotion: [
+        CellSet::from_positions(&[h!("b7"), h!("c8"), h!("d9"), h!("e10"), h!("f11"), h!("g10"), h!("h9"), h!("i8"), h!("k7")]),
+        CellSet::from_positions(&[h!("b2"), h!("c2"), h!("d2"), h!("e2"), h!("f2"), h!("g2"), h!("h2"), h!("i2"), h!("k2")]),
+    ],
+    pawn_advance: [Direction::North, Direction::South],
+    pawn_captures: [[Direction::NorthWest, Direction::NorthEast], [Direction::SouthEast, Direction::SouthWest]],
+    castling: [
+        Some(Castling {
+            color: Color::White,
+            king: h!("g2"),
+            rook: h!("k2"),
+            king_to: h!("i2"),
+            rook_to: h!("h2"),
+            path: &[h!("h2"), h!("i2")],
+        }),
+        Some(Castling {
+            color: Color::White,
+            king: h!("g2"),
+            rook: h!("b2"),
+            king_to: h!("e2"),
+            rook_to: h!("f2"),
+            path: &[h!("f2"), h!("e2"), h!("d2"), h!("c2")],
+        }),
+        Some(Castling {
+            color: Color::Black,
+            king: h!("g10"),
+            rook: h!("k7"),
+            king_to: h!("i8"),
+            rook_to: h!("h9"),
+            path: &[h!("h9"), h!("i8")],
+        }),
+        Some(Castling {
+            color: Color::Black,
+            king: h!("g10"),
+            rook: h!("b7"),
+            king_to: h!("e10"),
+            rook_to: h!("f11"),
+            path: &[h!("f11"), h!("e10"), h!("d9"), h!("c8")],
+        }),
+    ],
+    name_offset: (1, 1),
+};
+
+/// Mini hexchess, on the 37 cells within three steps of the center. Files
+/// and ranks are named from the remaining cells, so mini's a1 is gliński's c3.
+const MINI: BoardGeometry = BoardGeometry {
+    cells: CellSet::from_positions(&[
+        h!("c3"), h!("c4"), h!("c5"), h!("c6"),
+        h!("d3"), h!("d4"), h!("d5"), h!("d6"), h!("d7"),
+        h!("e3"), h!("e4"), h!("e5"), h!("e6"), h!("e7"), h!("e8"),
+        h!("f3"), h!("f4"), h!("f5"), h!("f6"), h!("f7"), h!("f8"), h!("f9"),
+        h!("g3"), h!("g4"), h!("g5"), h!("g6"), h!("g7"), h!("g8"),
+        h!("h3"), h!("h4"), h!("h5"), h!("h6"), h!("h7"),
+        h!("i3"), h!("i4"), h!("i5"), h!("i6"),
+    ]),
+    initial_position: "b/qpk/np1pn/rp3pr/p5p/PPPPPPP/RNQBKNR w - 0 1",
+    double_step: [CellSet::from_positions(&[]), CellSet::from_positions(&[])],
+    en_passant: [CellSet::from_positions(&[]), CellSet::from_positions(&[])],
+    promotion: [
+        CellSet::from_positions(&[h!("c6"), h!("d7"), h!("e8"), h!("f9"), h!("g8"), h!("h7"), h!("i6")]),
+        CellSet::from_positions(&[h!("c3"), h!("d3"), h!("e3"), h!("f3"), h!("g3"), h!("h3"), h!("i3")]),
+    ],
+    pawn_advance: [Direction::North, Direction::South],
+    pawn_captures: [[Direction::NorthWest, Direction::NorthEast], [Direction::SouthEast, Direction::SouthWest]],
+    castling: [None; 4],
+    name_offset: (2, 2),
+};
+
+/// Center of the board and the ring around it, king of the hill's goal
+const HILL: CellSet = CellSet::from_positions(&[h!("f6"), h!("f7"), h!("g6"), h!("g5"), h!("f5"), h!("e5"), h!("e6")]);
+
+/// Hexagonal chess variant
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Variant {
+    /// Gliński's hexagonal chess, on the full 91 cell board
+    #[default]
+    Glinski,
+
+    /// McCooey's hexagonal chess, with seven pawns and diagonal pawn captures
+    McCooey,
+
+    /// Shafran's hexagonal chess, on a 70 cell board with castling
+    Shafran,
+
+    /// Mini hexchess, on a 37 cell board without pawn double steps
+    Mini,
+
+    /// Gliński's hexagonal chess, with captured pieces dropped back onto the board
+    Crazyhouse,
+
+    /// Gliński's hexagonal chess, also won by moving a king to the center of the board
+    KingOfTheHill,
+
+    /// Gliński's hexagonal chess, also won by giving check three times
+    ThreeCheck,
+
+    /// Gliński's hexagonal chess with forced captures, won by losing every piece or being stalemated
+    Antichess,
+
+    /// Gliński's hexagonal chess without check, won by capturing the king, and usually played
+    /// with each player seeing only the cells their pieces could move to
+    Dark,
+}
+
+impl Variant {
+    /// Every supported variant
+    pub const ALL: [Variant; 9] = [
+        Variant::Glinski,
+        Variant::McCooey,
+        Variant::Shafran,
+        Variant::Mini,
+        Variant::Crazyhouse,
+        Variant::KingOfTheHill,
+        Variant::ThreeCheck,
+        Variant::Antichess,
+        Variant::Dark,
+    ];
+
+    /// get the board geometry of the variant
+    pub fn geometry(&self) -> &'static BoardGeometry {
+        match self {
+            Variant::Glinski |
+            Variant::Crazyhouse |
+            Variant::KingOfTheHill |
+            Variant::ThreeCheck |
+            Variant::Antichess |
+            Variant::Dark => &GLINSKI,
+            Variant::McCooey => &MCCOOEY,
+            Variant::Shafran => &SHAFRAN,
+            Variant::Mini => &MINI,
+        }
+    }
+
+    /// parse a move written with the variant's position names, including promotions to king
+    pub fn parse_san(&self, source: &str) -> Result<San, String> {
+        let geometry = self.geometry();
+
+        let unpromoted = match source.strip_suffix('k') {
+            Some(unpromoted) if self.has_king_promotion() && !source.contains('@') => unpromoted,
+            _ => return geometry.parse_san(source),
+        };
+
+        let san = geometry.parse_san(unpromoted)?;
+
+        if san.promotion.is_some() {
+            return Err("post promotion character".to_string());
+        }
+
+        if !geometry.is_promotion(san.to, Color::White) && !geometry.is_promotion(san.to, Color::Black) {
+            return Err(format!("invalid promotion position: {}", geometry.position_name(san.to).unwrap_or_default()));
+        }
+
+        Ok(San { promotion: Some(PromotionPiece::King), ..san })
+    }
+
+    /// get the number of checks that wins the game, if giving check can win
+    pub fn check_limit(&self) -> Option<u8> {
+        match self {
+            Variant::ThreeCheck => Some(3),
+            _ => None,
+        }
+    }
+
+    /// test if captured pieces go to the capturer's hand, to be dropped on empty cells
+    pub fn has_drops(&self) -> bool {
+        *self == Variant::Crazyhouse
+    }
+
+    /// test if a piece that can capture must do so
+    pub fn has_forced_captures(&self) -> bool {
+        *self == Variant::Antichess
+    }
+
+    /// test if capturing a king wins the game
+    pub fn has_king_capture(&self) -> bool {
+        *self == Variant::Dark
+    }
+
+    /// test if pawns may promote to kings, allowing more than one king of a color
+    pub fn has_king_promotion(&self) -> bool {
+        *self == Variant::Antichess
+    }
+
+    /// test if kings must be kept out of check, otherwise they may be captured
+    pub fn has_royal_king(&self) -> bool {
+        !matches!(self, Variant::Antichess | Variant::Dark)
+    }
+
+    /// get the cells that win the game when a king reaches them
+    pub fn hill(&self) -> CellSet {
+        match self {
+            Variant::KingOfTheHill => HILL,
+            _ => CellSet::default(),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Variant::Glinski => write!(f, "glinski"),
+            Variant::McCooey => write!(f, "mccooey"),
+            Variant::Shafran => write!(f, "shafran"),
+            Variant::Mini => write!(f, "mini"),
+            Variant::Crazyhouse => write!(f, "crazyhouse"),
+            Variant::KingOfTheHill => write!(f, "kingofthehill"),
+            Variant::ThreeCheck => write!(f, "threecheck"),
+            Variant::Antichess => write!(f, "antichess"),
+            Variant::Dark => write!(f, "dark"),
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Variant::ALL
+            .into_iter()
+            .find(|variant| variant.to_string() == source)
+            .ok_or_else(|| format!("invalid variant: {}", source))
+    }
+}
+
+/// split a leading position name, a file followed by it's rank digits, from a string
+fn split_position(source: &str) -> (&str, &str) {
+    let end = source
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !c.is_ascii_digit())
+        .map(|(i, _)| i)
+        .unwrap_or(source.len());
+
+    source.split_at(end)
+}
+
+/// get the array index of a color in per-color tables
+pub(crate) fn side(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::h;
+    use crate::hexchess::utils::{
+        is_black_promotion_position,
+        is_legal_black_en_passant,
+        is_legal_white_en_passant_position,
+        is_white_promotion_position,
+    };
+    use super::*;
+
+    #[test]
+    fn cell_set() {
+        let set = CellSet::from_positions(&[h!("f6"), h!("f11"), h!("l1")]);
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(h!("f6")));
+        assert!(!set.contains(h!("f5")));
+        assert!(!set.contains(200));
+        assert_eq!(set.iter().collect::<Vec<u8>>(), vec![h!("f11"), h!("f6"), h!("l1")]);
+        assert!(CellSet::default().is_empty());
+    }
+
+    #[test]
+    fn glinski_board() {
+        let geometry = Variant::Glinski.geometry();
+
+        assert_eq!(geometry.cells.len(), 91);
+
+        let lengths: Vec<usize> = geometry.rows().iter().map(|row| row.len()).collect();
+        assert_eq!(lengths, vec![1, 3, 5, 7, 9, 11, 11, 11, 11, 11, 11]);
+    }
+
+    #[test]
+    fn glinski_pawn_tables() {
+        let geometry = Variant::Glinski.geometry();
+
+        for position in 0u8..91u8 {
+            assert_eq!(geometry.is_promotion(position, Color::White), is_white_promotion_position(&position));
+            assert_eq!(geometry.is_promotion(position, Color::Black), is_black_promotion_position(&position));
+            assert_eq!(geometry.is_en_passant(position, Color::White), is_legal_white_en_passant_position(&position));
+            assert_eq!(geometry.is_en_passant(position, Color::Black), is_legal_black_en_passant(&position));
+        }
+    }
+
+    #[test]
+    fn shafran_board() {
+        let geometry = Variant::Shafran.geometry();
+
+        assert_eq!(geometry.cells.len(), 70);
+
+        let lengths: Vec<usize> = geometry.rows().iter().map(|row| row.len()).collect();
+        assert_eq!(lengths, vec![1, 3, 5, 7, 9, 9, 9, 9, 9, 9]);
+
+        assert_eq!(geometry.flip(h!("g2")), h!("g10"));
+        assert_eq!(geometry.flip(h!("b2")), h!("b7"));
+    }
+
+    #[test]
+    fn shafran_names() {
+        let geometry = Variant::Shafran.geometry();
+
+        assert_eq!(geometry.position_name(h!("b2")), Some("a1".to_string()));
+        assert_eq!(geometry.position_name(h!("f11")), Some("e10".to_string()));
+        assert_eq!(geometry.position_name(h!("a1")), None);
+        assert_eq!(geometry.position_index("i6"), Some(h!("k7")));
+        assert_eq!(geometry.position_index("a7"), None);
+        assert_eq!(geometry.position_index("e01"), None);
+
+        assert_eq!(geometry.parse_san("e2e4"), Ok(San { from: h!("f3"), promotion: None, to: h!("f5") }));
+        assert_eq!(geometry.parse_san("a2a1q"), Ok(San { from: h!("b3"), promotion: Some(PromotionPiece::Queen), to: h!("b2") }));
+        assert_eq!(geometry.parse_san("e2e3q"), Err("invalid promotion position: e3".to_string()));
+        assert_eq!(geometry.parse_san("l1l2"), Err("invalid from position: l1".to_string()));
+        assert_eq!(geometry.format_san(&San { from: h!("f3"), promotion: None, to: h!("f5") }), "e2e4");
+    }
+
+    #[test]
+    fn mini_board() {
+        let geometry = Variant::Mini.geometry();
+
+        assert_eq!(geometry.cells.len(), 37);
+
+        let lengths: Vec<usize> = geometry.rows().iter().map(|row| row.len()).collect();
+        assert_eq!(lengths, vec![1, 3, 5, 7, 7, 7, 7]);
+
+        assert_eq!(geometry.position_name(h!("c3")), Some("a1".to_string()));
+        assert_eq!(geometry.position_name(h!("f9")), Some("d7".to_string()));
+        assert_eq!(geometry.position_index("g4"), Some(h!("i6")));
+        assert_eq!(geometry.position_index("g5"), None);
+        assert_eq!(geometry.step(h!("f9"), 0), None);
+        assert!(geometry.double_step.iter().all(|cells| cells.is_empty()));
+    }
+
+    #[test]
+    fn king_promotions() {
+        let promotion = San { from: h!("f10"), promotion: Some(PromotionPiece::King), to: h!("f11") };
+
+        assert_eq!(Variant::Antichess.parse_san("f10f11k"), Ok(promotion));
+        assert_eq!(Variant::Antichess.parse_san("f10f11"), Ok(San { promotion: None, ..promotion }));
+        assert_eq!(Variant::Antichess.parse_san("f9f10k"), Err("invalid promotion position: f10".to_string()));
+        assert_eq!(Variant::Antichess.parse_san("f10f11qk"), Err("post promotion character".to_string()));
+        assert_eq!(Variant::Glinski.parse_san("f10f11k"), Err("invalid promotion piece: k".to_string()));
+        assert_eq!(Variant::Glinski.parse_san("f10f11q"), Ok(San { promotion: Some(PromotionPiece::Queen), ..promotion }));
+    }
+
+    #[test]
+    fn variant_names() {
+        for variant in Variant::ALL {
+            assert_eq!(variant.to_string().parse::<Variant>(), Ok(variant));
+        }
+
+        assert_eq!(Variant::default(), Variant::Glinski);
+        assert_eq!("whoops".parse::<Variant>(), Err("invalid variant: whoops".to_string()));
+    }
+}
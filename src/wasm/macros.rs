@@ -97,6 +97,6 @@ macro_rules! h {
 #[macro_export]
 macro_rules! s {
     ($expression:expr) => {
-        crate::hexchess::san::San::from(&$expression.to_string()).unwrap()
+        crate::hexchess::san::San::parse(&$expression.to_string()).unwrap()
     };
 }
@@ -0,0 +1,82 @@
+import { bench, describe } from 'vitest'
+import { index } from '../src'
+
+import {
+  apply,
+  currentMoves,
+  Game,
+  initHexchess,
+  isCheck,
+  movesFrom
+} from '../dist/wasm?init'
+
+const sequence = 'g4g6 f7g6 f5f7 g6f6 e4e5 f6e5'
+
+describe('currentMoves', () => {
+  const hexchess = apply(initHexchess(), sequence)
+  const game = new Game()
+
+  game.apply(sequence)
+
+  bench('functions', () => {
+    currentMoves(hexchess)
+  })
+
+  bench('Game', () => {
+    game.currentMoves()
+  })
+})
+
+describe('isCheck', () => {
+  const hexchess = apply(initHexchess(), sequence)
+  const game = new Game()
+
+  game.apply(sequence)
+
+  bench('functions', () => {
+    isCheck(hexchess)
+  })
+
+  bench('Game', () => {
+    game.isCheck()
+  })
+})
+
+describe('movesFrom', () => {
+  const hexchess = initHexchess()
+  const game = new Game()
+
+  bench('functions', () => {
+    movesFrom(hexchess, index('f5'))
+  })
+
+  bench('Game', () => {
+    game.movesFrom(index('f5'))
+  })
+})
+
+describe('play and take back', () => {
+  bench('functions', () => {
+    const history = [initHexchess()]
+
+    for (const san of sequence.split(' ')) {
+      history.push(apply(history[history.length - 1], san))
+    }
+
+    while (history.length > 1) {
+      history.pop()
+    }
+  })
+
+  bench('Game', () => {
+    const game = new Game()
+
+    game.apply(sequence)
+
+    while (game.undo()) {
+      // take back every move
+    }
+
+    game.free()
+  })
+})
@@ -9,20 +9,46 @@ import {
   apply,
   applyMove,
   applyMoveUnsafe,
+  attackMap,
+  attackersOf,
+  cellAt,
+  cellCenter,
+  cellCorners,
+  checkers,
   createHexchess,
   currentMoves,
+  decodeGame,
+  discoveredCheckCandidates,
+  encodeGame,
   findKing,
+  flipColors,
+  fromShareCode,
+  Game,
   get,
   initHexchess,
   isCheck,
   isCheckmate,
   isStalemate,
+  layoutHeight,
+  layoutWidth,
+  mirror,
   movesFrom,
   movesFromUnsafe,
   parseHexchess,
+  parseHexchessStrict,
   parseSan,
+  pinned,
+  redactedView,
+  renderSvg,
+  shuffleCount,
+  shuffleHexchess,
+  shuffleIndex,
+  shuffleSeeded,
   stringifyHexchess,
-  stringifySan
+  stringifySan,
+  toShareCode,
+  validateHexchess,
+  visibleCells
 } from '../dist/wasm?init'
 
 test('apply', () => {
@@ -35,6 +61,7 @@ test('applyMoveUnsafe', () => {
   const hexchess = applyMoveUnsafe(initHexchess(), parseSan('b1b6'))
 
   expect(stringifyHexchess(hexchess)).toEqual('b/qbk/n1b1n/r5r/ppppppppp/1P9/5P5/4P1P4/3P1B1P3/2P2B2P2/2RNQBKNRP1 b - 0 1')
+  expect(() => applyMoveUnsafe(initHexchess(), parseSan('a4a5'))).toThrow('cannot apply move from empty position')
 })
 test('applyMove', () => {
   const hexchess = applyMove(initHexchess(), {
@@ -46,6 +73,50 @@ test('applyMove', () => {
   expect(hexchess.board[index('g5')]).toBe('P')
 })
 
+test('attackMap', () => {
+  const hexchess = initHexchess()
+
+  expect(attackMap(hexchess, 'w')).toHaveLength(91)
+  expect(attackMap(hexchess, 'w')[index('f4')]).toBe(4)
+  expect(attackMap(hexchess, 'b')[index('f7')]).toBe(2)
+  expect(() => attackMap(hexchess, 'x' as any)).toThrow('invalid color: x')
+})
+
+test('attackersOf', () => {
+  const hexchess = parseHexchess('1/3/5/7/9/11/4p1p4/11/11/11/11 w - 0 1')
+
+  expect(Array.from(attackersOf(hexchess, index('f5'), 'b'))).toEqual([index('e5'), index('g5')])
+  expect(Array.from(attackersOf(hexchess, index('f5'), 'w'))).toEqual([])
+  expect(() => attackersOf(hexchess, 91, 'w')).toThrow('invalid position: 91')
+})
+
+test('cellAt', () => {
+  const layout = { flipped: false, orientation: 'flat', size: 10 } as const
+
+  expect(cellAt(layout, 85, 95.26279441628824)).toBe(index('f6'))
+  expect(cellAt(layout, 0, 0)).toBeUndefined()
+})
+
+test('cellCenter', () => {
+  const layout = { flipped: true, orientation: 'flat', size: 10 } as const
+
+  expect(cellCenter(layout, index('f6'))).toEqual(cellCenter({ ...layout, flipped: false }, index('f6')))
+  expect(cellCenter(layout, index('f1'))).toEqual(cellCenter({ ...layout, flipped: false }, index('f11')))
+})
+
+test('cellCorners', () => {
+  const layout = { flipped: false, orientation: 'pointy', size: 10 } as const
+
+  expect(cellCorners(layout, index('f6'))).toHaveLength(6)
+  expect(() => cellCorners(layout, 91)).toThrow('invalid position: 91')
+})
+
+test('checkers', () => {
+  const hexchess = parseHexchess('K/3/5/4n2/9/5r5/11/11/11/11/11 w - 0 1')
+
+  expect(Array.from(checkers(hexchess))).toEqual([index('g8'), index('f6')])
+})
+
 test('createHexchess', () => {
   const hexchess = createHexchess()
 
@@ -88,11 +159,60 @@ test('currentMoves', () => {
   ])
 })
 
+test('discoveredCheckCandidates', () => {
+  const hexchess = parseHexchess('k/3/5/3N3/9/11/11/11/5R5/11/11 w - 0 1')
+
+  expect(Array.from(discoveredCheckCandidates(hexchess))).toEqual([index('f8')])
+})
+
+test('encodeGame', () => {
+  const moves = ['g4g6', 'f7g6', 'f5f7', 'g6f6'].map(san => parseSan(san))
+  const bytes = encodeGame(undefined, moves)
+
+  expect(bytes).toHaveLength(6)
+  expect(decodeGame(bytes).moves.map(san => stringifySan(san))).toEqual(['g4g6', 'f7g6', 'f5f7', 'g6f6'])
+  expect(() => encodeGame(undefined, [parseSan('a1a2')])).toThrow()
+})
+
 test('findKing', () => {
   const hexchess = initHexchess()
 
   expect(findKing(hexchess, 'b')).toEqual(index('g10'))
   expect(findKing(hexchess, 'w')).toEqual(index('g1'))
+  expect(() => findKing(hexchess, 'x' as any)).toThrow('invalid color: x')
+})
+
+test('flipColors', () => {
+  const hexchess = flipColors(apply(initHexchess(), 'g4g6'))
+
+  expect(stringifyHexchess(hexchess)).toBe('b/kbq/n1b1n/r5r/ppp1ppppp/11/4pP5/4P1P4/3P1B1P3/2P2B2P2/1PRNKBQNRP1 w e6 0 1')
+})
+
+test('Game', () => {
+  const game = new Game()
+
+  game.apply('g4g6 f7g6')
+  game.applyMove(parseSan('f5f7'))
+
+  expect(game.history().map(san => stringifySan(san))).toEqual(['g4g6', 'f7g6', 'f5f7'])
+  expect(game.isCheck()).toBe(false)
+  expect(game.status()).toBe('ongoing')
+  expect(game.movesFrom(index('g6'))).toEqual(movesFrom(game.toHexchess(), index('g6')))
+  expect(game.currentMoves()).toEqual(currentMoves(game.toHexchess()))
+  expect(stringifySan(game.undo()!)).toBe('f5f7')
+  expect(game.fen()).toBe('b/qbk/n1b1n/r5r/pppp1pppp/6p4/5P5/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 2')
+  expect(() => game.apply('a1a2')).toThrow('illegal move at index 0: a1a2')
+  expect(() => game.movesFrom(91)).toThrow('invalid position: 91')
+  expect(() => new Game('whoops')).toThrow()
+
+  const checkmate = new Game('K/3/5/3q3/2q6/11/11/11/11/11/11 b - 0 1')
+
+  checkmate.apply('d7f9')
+
+  expect(checkmate.status()).toBe('checkmate')
+
+  game.free()
+  checkmate.free()
 })
 
 test('get', () => {
@@ -143,6 +263,20 @@ test('isStalemate', () => {
   expect(isStalemate(hexchess)).toBe(false)
 })
 
+test('layoutHeight', () => {
+  expect(layoutHeight({ flipped: false, orientation: 'pointy', size: 10 })).toBe(170)
+})
+
+test('layoutWidth', () => {
+  expect(layoutWidth({ flipped: false, orientation: 'flat', size: 10 })).toBe(170)
+})
+
+test('mirror', () => {
+  const hexchess = mirror(initHexchess())
+
+  expect(stringifyHexchess(hexchess)).toBe('b/kbq/n1b1n/r5r/ppppppppp/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNKBQNRP1 w - 0 1')
+})
+
 test('movesFrom', () => {
   const hexchess = initHexchess()
 
@@ -156,18 +290,54 @@ test('movesFromUnsafe', () => {
   const hexchess = initHexchess()
 
   expect(movesFromUnsafe(hexchess, index('a1'))).toEqual([])
+  expect(() => movesFromUnsafe(hexchess, 91)).toThrow('invalid position: 91')
 })
 
 test('parseHexchess', () => {
   const hexchess = parseHexchess(initialPosition)
 
   expect(hexchess).toEqual(initHexchess())
+  expect(() => parseHexchess('whoops')).toThrow()
+})
+
+test('parseHexchessStrict', () => {
+  expect(stringifyHexchess(parseHexchessStrict(initialPosition))).toBe(initialPosition)
+  expect(() => parseHexchessStrict('1/3/5/7/9/11/11/11/11/11/11 w - 0 1')).toThrow('missing white king; missing black king')
+  expect(stringifyHexchess(parseHexchessStrict('1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1', 'antichess'))).toBe('1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1')
 })
 
 test('parseSan', () => {
   const san = parseSan('g4g6')
 
   expect(san).toEqual({ from: 53, promotion: null, to: 31 })
+  expect(parseSan('f1i1', 'shafran')).toEqual({ from: index('g2'), promotion: null, to: index('k2') })
+  expect(() => parseSan('whoops')).toThrow()
+  expect(() => parseSan('a1a2', 'shafran')).not.toThrow()
+  expect(() => parseSan('k1k2', 'shafran')).toThrow()
+})
+
+test('pinned', () => {
+  const hexchess = parseHexchess('1/3/5/7/4K4/5R5/5q5/11/11/11/11 w - 0 1')
+
+  expect(pinned(hexchess, 'w')).toEqual([
+    { direction: 6, pinned: index('f6'), pinner: index('f5') },
+  ])
+
+  expect(pinned(hexchess, 'b')).toEqual([])
+})
+
+test('redactedView', () => {
+  const hexchess = redactedView(initHexchess(), 'w')
+
+  expect(stringifyHexchess(hexchess)).toBe('1/3/5/7/9/11/5P5/4P1P4/3P1B1P3/2P2B2P2/1PRNQBKNRP1 w - 0 1')
+  expect(() => redactedView(initHexchess(), 'x')).toThrow('invalid color: x')
+})
+
+test('renderSvg', () => {
+  const svg = renderSvg(initHexchess(), { flipped: true, lastMove: parseSan('g4g6') })
+
+  expect(svg.startsWith('<svg')).toBe(true)
+  expect(svg.match(/<polygon/g)).toHaveLength(93)
 })
 
 test('stringifyHexchess', () => {
@@ -180,4 +350,150 @@ test('stringifySan', () => {
   const san = parseSan('g4g6')
 
   expect(stringifySan(san)).toEqual('g4g6')
+  expect(stringifySan({ from: index('g2'), promotion: null, to: index('k2') }, 'shafran')).toEqual('f1i1')
+  expect(() => stringifySan({ from: 200, promotion: null, to: 0 })).toThrow()
+  expect(() => stringifySan({ from: index('a1'), promotion: null, to: index('a2') }, 'shafran')).toThrow()
+})
+
+test('toShareCode', () => {
+  const hexchess = apply(initHexchess(), 'g4g6 f7f6')
+  const code = toShareCode(hexchess)
+
+  expect(code).toMatch(/^[A-Za-z0-9_-]{47}$/)
+  expect(stringifyHexchess(fromShareCode(code))).toBe(stringifyHexchess(hexchess))
+  expect(() => fromShareCode('whoops!')).toThrow()
+})
+
+test('validateHexchess', () => {
+  expect(validateHexchess(initHexchess())).toEqual([])
+  expect(validateHexchess(createHexchess())).toEqual(['missing white king', 'missing black king'])
+})
+
+test('visibleCells', () => {
+  const visible = visibleCells(initHexchess(), 'w')
+
+  expect(visible).toHaveLength(91)
+  expect(visible.filter(n => n === 1)).toHaveLength(48)
+  expect(visible[index('g6')]).toBe(1)
+  expect(visible[index('g10')]).toBe(0)
+})
+
+test('variants', () => {
+  const hexchess = initHexchess('mccooey')
+
+  expect(hexchess.variant).toBe('mccooey')
+  expect(initHexchess().variant).toBeUndefined()
+  expect(stringifyHexchess(hexchess)).toBe('b/qbk/rnbnr/ppppppp/9/11/11/5P5/4PBP4/3PNBNP3/2PRQBKRP2 w - 0 1')
+  expect(currentMoves(hexchess)).toHaveLength(31)
+  expect(parseHexchess(stringifyHexchess(hexchess), 'mccooey')).toEqual(hexchess)
+  expect(new Game(undefined, 'mccooey').currentMoves()).toHaveLength(31)
+})
+
+test('shafran castling', () => {
+  const hexchess = parseHexchess('k/3/5/7/9/9/9/9/9/R4K2R w KQ - 0 1', 'shafran')
+
+  expect(hexchess.castling).toEqual([true, true, false, false])
+  expect(initHexchess().castling).toBeUndefined()
+  expect(stringifyHexchess(apply(hexchess, 'f1i1'))).toBe('k/3/5/7/9/9/9/9/9/R5RK1 b - - 1 1')
+  expect(currentMoves(initHexchess('shafran'))).toHaveLength(39)
+  expect(initHexchess('shafran').board).toHaveLength(70)
+})
+
+test('shafran san round trip', () => {
+  const hexchess = parseHexchess('k/3/5/7/9/9/9/9/9/R4K2R w KQ - 0 1', 'shafran')
+
+  for (const position of [initHexchess('shafran'), hexchess]) {
+    for (const san of currentMoves(position)) {
+      const notation = stringifySan(san, 'shafran')
+
+      expect(parseSan(notation, 'shafran')).toEqual(san)
+      expect(stringifyHexchess(apply(position, notation))).toBe(stringifyHexchess(applyMove(position, san)))
+    }
+  }
+})
+
+test('shuffle', () => {
+  const hexchess = shuffleHexchess(0)
+
+  expect(shuffleCount()).toBe(3780)
+  expect(stringifyHexchess(hexchess)).toBe('k/brn/b1r1n/b5q/ppppppppp/11/5P5/4P1P4/3P1R1P3/2P2R2P2/1PBBBKNNQP1 w - 0 1')
+  expect(shuffleIndex(parseHexchess(stringifyHexchess(hexchess)))).toBe(0)
+  expect(shuffleIndex(initHexchess())).toBe(3520)
+  expect(shuffleSeeded(42)).toEqual(shuffleSeeded(42))
+  expect(() => shuffleHexchess(3780)).toThrowError('invalid shuffle index: 3780')
+})
+
+test('antichess', () => {
+  const hexchess = apply(initHexchess('antichess'), 'g4g6')
+
+  expect(currentMoves(hexchess).map(san => stringifySan(san))).toEqual(['f7g6'])
+  expect(() => apply(hexchess, 'e7e6')).toThrowError()
+
+  const game = new Game('1/3/5/7/9/11/11/11/11/5p5/11 b - 0 1', 'antichess')
+
+  game.apply('f2f1k')
+
+  expect(game.fen()).toBe('1/3/5/7/9/11/11/11/11/11/5k5 w - 0 2')
+  expect(game.status()).toBe('stalemate')
+
+  game.free()
+})
+
+test('crazyhouse', () => {
+  const hexchess = apply(initHexchess('crazyhouse'), 'g4g6 f7g6 f5f7')
+
+  expect(stringifyHexchess(hexchess)).toBe('b/qbk/n1b1n/r5r/ppppPpppp/6p4/11/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1[p] b f6 0 2')
+  expect(hexchess.pockets).toEqual([[0, 0, 0, 0, 0], [1, 0, 0, 0, 0]])
+  expect(stringifyHexchess(apply(hexchess, 'P@f8'))).toBe('b/qbk/n1b1n/r2p2r/ppppPpppp/6p4/11/4P6/3P1B1P3/2P2B2P2/1PRNQBKNRP1[] w - 0 3')
+
+  const promoted = apply(parseHexchess('1/qP1/5/7/9/2k8/11/11/11/5K5/11[] w - 0 1', 'crazyhouse'), 'f10f11q')
+
+  expect(promoted.promoted).toEqual([index('f11')])
+  expect(stringifyHexchess(apply(promoted, 'e10f11'))).toBe('q/3/5/7/9/2k8/11/11/11/5K5/11[p] w - 0 2')
+})
+
+test('dark', () => {
+  const game = new Game('k/3/5/7/9/11/11/11/11/5q5/5K5 w - 0 1', 'dark')
+
+  game.apply('f1e1 f2e1')
+
+  expect(game.status()).toBe('kingcapture')
+
+  game.free()
+})
+
+test('king of the hill', () => {
+  const game = new Game('1/1k1/5/7/9/11/11/5K5/11/11/11 w - 0 1', 'kingofthehill')
+
+  game.apply('f4f5')
+
+  expect(game.status()).toBe('kingofthehill')
+  expect(() => game.apply('e9e8')).toThrow()
+
+  game.free()
+})
+
+test('three check', () => {
+  const game = new Game('k/3/5/7/9/11/11/11/11/11/R4K5 w - 2+0 0 1', 'threecheck')
+
+  game.apply('a1a6')
+
+  expect(game.fen()).toBe('k/3/5/7/9/R10/11/11/11/11/5K5 b - 3+0 1 1')
+  expect(game.status()).toBe('threecheck')
+
+  game.free()
+
+  expect(parseHexchess('k/3/5/7/9/11/11/11/11/11/R4K5 w - 2+0 0 1', 'threecheck').checks).toEqual([2, 0])
+  expect(initHexchess('threecheck').checks).toBeUndefined()
+})
+
+test('mini', () => {
+  const hexchess = initHexchess('mini')
+
+  expect(stringifyHexchess(hexchess)).toBe('b/qpk/np1pn/rp3pr/p5p/PPPPPPP/RNQBKNR w - 0 1')
+  expect(hexchess.board).toHaveLength(37)
+  expect(hexchess.board.slice(0, 4)).toEqual(['b', 'q', 'p', 'k'])
+  expect(stringifyHexchess(parseHexchess(stringifyHexchess(hexchess), 'mini'))).toBe(stringifyHexchess(hexchess))
+  expect(currentMoves(hexchess)).toHaveLength(15)
+  expect(() => apply(hexchess, 'b2b4')).toThrowError()
 })